@@ -23,6 +23,7 @@ enum Event {
     Redraw,
     AddString(String),
     Cancel,
+    Undo,
 }
 
 impl BlankField {
@@ -70,6 +71,12 @@ impl BlankField {
                                 KeyCode::Tab => Some(Event::NextField),
                                 KeyCode::BackTab => Some(Event::PrevField),
                                 KeyCode::Delete => Some(Event::RemoveNextChar),
+                                KeyCode::Char('z' | 'Z')
+                                    if k.modifiers
+                                        .contains(crossterm::event::KeyModifiers::CONTROL) =>
+                                {
+                                    Some(Event::Undo)
+                                }
                                 KeyCode::Char(c) => Some(Event::AddChar(c)),
                                 KeyCode::Esc => Some(Event::Cancel),
                                 _ => None,
@@ -99,7 +106,7 @@ impl BlankField {
             Event::PrevField => return Some(event),
             Event::Redraw => (),
             Event::AddString(s) => s.chars().for_each(|c| self.add_char(c)),
-            Event::Cancel => return Some(event),
+            Event::Cancel | Event::Undo => return Some(event),
         }
         None
     }
@@ -118,6 +125,7 @@ impl BlankField {
                     Event::NextField => return Ok(ResultKind::NextBlock),
                     Event::PrevField => return Ok(ResultKind::PrevBlock),
                     Event::Cancel => return Ok(ResultKind::Canceled),
+                    Event::Undo => return Ok(ResultKind::Undo),
                     _ => unreachable!(),
                 }
             }
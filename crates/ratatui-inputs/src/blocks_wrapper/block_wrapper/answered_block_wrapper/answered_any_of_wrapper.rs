@@ -0,0 +1,45 @@
+use ratatui::{
+    style::Stylize,
+    text::{Line, Span},
+};
+
+#[derive(Debug)]
+pub struct AnsweredAnyOfWrapper {
+    items: Vec<String>,
+    user_answer: Vec<usize>,
+    correct_answer: Vec<usize>,
+}
+impl AnsweredAnyOfWrapper {
+    pub fn new(items: Vec<String>, user_answer: Vec<usize>, correct_answer: Vec<usize>) -> Self {
+        Self {
+            items,
+            user_answer,
+            correct_answer,
+        }
+    }
+    /// - correctly selected items are green
+    /// - false positives are red and crossed out
+    /// - missed items are yellow
+    pub fn as_lines(&self) -> Vec<Line<'_>> {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let selected = self.user_answer.contains(&i);
+                let correct = self.correct_answer.contains(&i);
+                let checkbox = if selected { " [x] " } else { " [ ] " };
+                let line = Line::from(vec![Span::raw(checkbox).white(), Span::raw(x.as_str())]);
+                match (selected, correct) {
+                    (true, true) => line.green(),
+                    (true, false) => line.red().crossed_out(),
+                    (false, true) => {
+                        let mut line = line.yellow();
+                        line.push_span(Span::raw(" (missed)").italic());
+                        line
+                    }
+                    (false, false) => line,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,43 @@
+use ratatui::{
+    style::Stylize,
+    text::{Line, Span},
+};
+
+#[derive(Debug)]
+pub struct AnsweredOrderWrapper {
+    items: Vec<String>,
+    user_answer: Vec<usize>,
+    correct_answer: Vec<usize>,
+}
+impl AnsweredOrderWrapper {
+    pub fn new(items: Vec<String>, user_answer: Vec<usize>, correct_answer: Vec<usize>) -> Self {
+        Self {
+            items,
+            user_answer,
+            correct_answer,
+        }
+    }
+    /// Items in order chosen by user.
+    /// Misplaced items are red and followed by their correct position.
+    pub fn as_lines(&self) -> Vec<Line<'_>> {
+        self.user_answer
+            .iter()
+            .enumerate()
+            .map(|(position, &item)| {
+                let prefix = Span::raw(format!(" {}. ", position + 1)).white();
+                let text = Span::raw(self.items[item].as_str());
+                if self.correct_answer.get(position) == Some(&item) {
+                    Line::from(vec![prefix, text]).green()
+                } else {
+                    let mut spans = vec![prefix, text.red()];
+                    if let Some(correct_position) =
+                        self.correct_answer.iter().position(|&x| x == item)
+                    {
+                        spans.push(Span::raw(format!(" -> {}", correct_position + 1)).yellow());
+                    }
+                    Line::from(spans)
+                }
+            })
+            .collect()
+    }
+}
@@ -11,7 +11,7 @@ impl From<Vec<s_text_input_f::ParagraphItemAnswered>> for AnsweredParagraphWrapp
     }
 }
 impl AnsweredParagraphWrapper {
-    pub fn as_line(&self) -> Line {
+    pub fn as_line(&self) -> Line<'_> {
         self.0.iter().flat_map(|x| x.as_spans()).collect()
     }
 }
@@ -39,7 +39,7 @@ impl From<s_text_input_f::ParagraphItemAnswered> for AnsweredParagraphItem {
     }
 }
 impl AnsweredParagraphItem {
-    pub fn as_spans(&self) -> Vec<Span> {
+    pub fn as_spans(&self) -> Vec<Span<'_>> {
         match self {
             AnsweredParagraphItem::Text(s) => {
                 vec![Span::raw(s)]
@@ -0,0 +1,216 @@
+use std::collections::BTreeSet;
+
+use crossterm::event::{KeyCode, KeyEventKind};
+use ratatui::{
+    style::{Style, Stylize},
+    text::{Line, Span},
+};
+
+use super::list_cursor::ListCursor;
+use crate::ResultKind;
+
+#[derive(Debug)]
+pub struct AnyOfWrapper {
+    items: Vec<String>,
+    cursor: ListCursor,
+    selected: BTreeSet<usize>,
+}
+impl From<Vec<String>> for AnyOfWrapper {
+    fn from(items: Vec<String>) -> Self {
+        Self {
+            items,
+            cursor: ListCursor::default(),
+            selected: BTreeSet::new(),
+        }
+    }
+}
+impl AnyOfWrapper {
+    pub fn finalize(self) -> Vec<String> {
+        self.selected.into_iter().map(|x| x.to_string()).collect()
+    }
+    pub fn get_input(
+        &mut self,
+        start_from_left: bool,
+        render: &mut impl FnMut(Vec<Line>) -> std::io::Result<()>,
+    ) -> Option<std::io::Result<ResultKind>> {
+        if start_from_left {
+            self.cursor.select_first(self.items.len())?;
+        } else {
+            self.cursor.select_last(self.items.len())?;
+        }
+        let mut render = |any_of: &AnyOfWrapper| {
+            let mut lines = any_of.as_lines();
+            lines[any_of.cursor.position()] = lines[any_of.cursor.position()]
+                .to_owned()
+                .patch_style(current_line_styles());
+            render(lines)
+        };
+        Some((|| loop {
+            render(self)?;
+            if let Some(event) = self.handle_event(Self::get_event()?) {
+                break Ok(event);
+            }
+        })())
+    }
+    fn toggle_current(&mut self) {
+        let current = self.cursor.position();
+        if !self.selected.remove(&current) {
+            self.selected.insert(current);
+        }
+    }
+
+    pub fn as_lines(&self) -> Vec<Line<'_>> {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let is_selected = self.selected.contains(&i);
+                let line = as_line(is_selected, x);
+                if is_selected {
+                    line.patch_style(selected_line_styles())
+                } else {
+                    line
+                }
+            })
+            .collect()
+    }
+}
+#[derive(Clone, Copy)]
+enum Event {
+    Toggle,
+    NextItem,
+    PrevItem,
+    NextBlock,
+    EnterKey,
+    PrevBlock,
+    Redraw,
+    Cancel,
+    Undo,
+}
+impl AnyOfWrapper {
+    #[allow(clippy::too_many_lines)]
+    fn get_event() -> std::io::Result<Event> {
+        Ok({
+            loop {
+                if let Some(x) = match crossterm::event::read()? {
+                    crossterm::event::Event::Key(k) => {
+                        if k.kind == KeyEventKind::Press {
+                            match k.code {
+                                KeyCode::Char(' ') => Some(Event::Toggle),
+                                KeyCode::Enter => Some(Event::EnterKey),
+                                KeyCode::Down | KeyCode::Char('j' | 'J') => Some(Event::NextItem),
+                                KeyCode::Up | KeyCode::Char('k' | 'K') => Some(Event::PrevItem),
+                                KeyCode::Tab => {
+                                    if cfg!(feature = "fast_tab_scroll") {
+                                        Some(Event::NextBlock)
+                                    } else {
+                                        Some(Event::NextItem)
+                                    }
+                                }
+                                KeyCode::BackTab => {
+                                    if cfg!(feature = "fast_tab_scroll") {
+                                        Some(Event::PrevBlock)
+                                    } else {
+                                        Some(Event::PrevItem)
+                                    }
+                                }
+                                KeyCode::Char('z' | 'Z')
+                                    if k.modifiers
+                                        .contains(crossterm::event::KeyModifiers::CONTROL) =>
+                                {
+                                    Some(Event::Undo)
+                                }
+                                KeyCode::Esc | KeyCode::Char('q' | 'Q') => Some(Event::Cancel),
+                                _ => None,
+                            }
+                        } else {
+                            None
+                        }
+                    }
+                    crossterm::event::Event::Resize(_, _) => Some(Event::Redraw),
+                    _ => None,
+                } {
+                    break x;
+                }
+            }
+        })
+    }
+    fn handle_event(&mut self, event: Event) -> Option<ResultKind> {
+        match event {
+            Event::Toggle => {
+                self.toggle_current();
+                None
+            }
+            Event::NextItem => {
+                let already_last_elem = !self.cursor.select_next(self.items.len()).unwrap();
+                if already_last_elem {
+                    Some(ResultKind::NextBlock)
+                } else {
+                    None
+                }
+            }
+            Event::PrevItem => {
+                let already_first_elem = !self.cursor.select_prev(self.items.len()).unwrap();
+                if already_first_elem {
+                    Some(ResultKind::PrevBlock)
+                } else {
+                    None
+                }
+            }
+            Event::NextBlock => Some(ResultKind::NextBlock),
+            Event::PrevBlock => Some(ResultKind::PrevBlock),
+            Event::EnterKey => Some(ResultKind::Ok),
+            Event::Redraw => None,
+            Event::Cancel => Some(ResultKind::Canceled),
+            Event::Undo => Some(ResultKind::Undo),
+        }
+    }
+}
+fn as_line(is_selected: bool, s: &str) -> Line<'_> {
+    let checkbox = if is_selected { " [x] " } else { " [ ] " };
+    Line::from(vec![Span::raw(checkbox).blue(), Span::raw(s)]).italic()
+}
+fn current_line_styles() -> Style {
+    Style::new().bold().fg(ratatui::style::Color::Magenta)
+}
+fn selected_line_styles() -> Style {
+    Style::new().bold().not_italic()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrapper() -> AnyOfWrapper {
+        AnyOfWrapper::from(vec!["a".into(), "b".into(), "c".into(), "d".into()])
+    }
+
+    #[test]
+    fn finalize_gives_sorted_indices() {
+        let mut any_of = wrapper();
+        for event in [
+            Event::NextItem,
+            Event::NextItem,
+            Event::NextItem,
+            Event::Toggle,
+            Event::PrevItem,
+            Event::PrevItem,
+            Event::PrevItem,
+            Event::Toggle,
+            Event::NextItem,
+            Event::Toggle,
+        ] {
+            any_of.handle_event(event);
+        }
+        assert_eq!(any_of.finalize(), ["0", "1", "3"]);
+    }
+
+    #[test]
+    fn toggled_twice_is_not_selected() {
+        let mut any_of = wrapper();
+        any_of.handle_event(Event::NextItem);
+        any_of.handle_event(Event::Toggle);
+        any_of.handle_event(Event::Toggle);
+        assert!(any_of.finalize().is_empty());
+    }
+}
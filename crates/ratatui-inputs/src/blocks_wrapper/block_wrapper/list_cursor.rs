@@ -0,0 +1,72 @@
+/// Position of current item in list, that has `len` items.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ListCursor {
+    position: usize,
+}
+impl ListCursor {
+    pub fn position(self) -> usize {
+        self.position
+    }
+    /// # Errors
+    /// if there is no items
+    pub fn select_first(&mut self, len: usize) -> Option<()> {
+        if len == 0 {
+            None
+        } else {
+            self.position = 0;
+            Some(())
+        }
+    }
+    /// # Errors
+    /// if there is no items
+    pub fn select_last(&mut self, len: usize) -> Option<()> {
+        if len == 0 {
+            None
+        } else {
+            self.position = len - 1;
+            Some(())
+        }
+    }
+    /// # Returns
+    /// - `Some(true)`  if next item selected
+    /// - `Some(false)` if it's last item already
+    /// - `None`        if there is no items
+    pub fn select_next(&mut self, len: usize) -> Option<bool> {
+        if len == 0 {
+            None
+        } else if self.position + 1 < len {
+            self.position += 1;
+            Some(true)
+        } else {
+            Some(false)
+        }
+    }
+    /// # Returns
+    /// - `Some(true)`  if prev item selected
+    /// - `Some(false)` if it's first item already
+    /// - `None`        if there is no items
+    pub fn select_prev(&mut self, len: usize) -> Option<bool> {
+        if len == 0 {
+            None
+        } else if let Some(x) = self.position.checked_sub(1) {
+            self.position = x;
+            Some(true)
+        } else {
+            Some(false)
+        }
+    }
+    /// Moves item under cursor one position down, cursor follows it.
+    pub fn move_down<T>(&mut self, items: &mut [T]) {
+        if self.position + 1 < items.len() {
+            items.swap(self.position, self.position + 1);
+            self.position += 1;
+        }
+    }
+    /// Moves item under cursor one position up, cursor follows it.
+    pub fn move_up<T>(&mut self, items: &mut [T]) {
+        if let Some(prev) = self.position.checked_sub(1) {
+            items.swap(self.position, prev);
+            self.position = prev;
+        }
+    }
+}
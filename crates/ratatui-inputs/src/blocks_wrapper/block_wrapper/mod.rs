@@ -4,8 +4,8 @@ use crate::ResultKind;
 
 #[derive(Debug)]
 pub enum BlockWrapper {
-    Order,
-    AnyOf,
+    Order(order_wrapper::OrderWrapper),
+    AnyOf(any_of_wrapper::AnyOfWrapper),
     OneOf(one_of_wrapper::OneOfWrapper),
     Paragraph(paragraph_wrapper::ParagraphWrapper),
     Answered(answered_block_wrapper::AnsweredBlockWrapper),
@@ -13,8 +13,12 @@ pub enum BlockWrapper {
 impl From<s_text_input_f::Block> for BlockWrapper {
     fn from(value: s_text_input_f::Block) -> Self {
         match value {
-            s_text_input_f::Block::Order(_) => todo!("`order` input not implemented"),
-            s_text_input_f::Block::AnyOf(_) => todo!("`any_of` input not implemented"),
+            s_text_input_f::Block::Order(items) => {
+                Self::Order(order_wrapper::OrderWrapper::from(items))
+            }
+            s_text_input_f::Block::AnyOf(items) => {
+                Self::AnyOf(any_of_wrapper::AnyOfWrapper::from(items))
+            }
             s_text_input_f::Block::OneOf(items) => {
                 Self::OneOf(one_of_wrapper::OneOfWrapper::from(items))
             }
@@ -31,8 +35,8 @@ impl From<s_text_input_f::Block> for BlockWrapper {
 impl BlockWrapper {
     pub fn finalize(self) -> Vec<String> {
         match self {
-            BlockWrapper::Order => todo!(),
-            BlockWrapper::AnyOf => todo!(),
+            BlockWrapper::Order(o) => o.finalize(),
+            BlockWrapper::AnyOf(a) => a.finalize(),
             BlockWrapper::OneOf(o) => o.finalize(),
             BlockWrapper::Paragraph(p) => p.finalize(),
             BlockWrapper::Answered(_) => vec![],
@@ -44,8 +48,8 @@ impl BlockWrapper {
         render: &mut impl FnMut(Vec<Line>) -> std::io::Result<()>,
     ) -> Option<std::io::Result<ResultKind>> {
         match self {
-            BlockWrapper::Order => todo!(),
-            BlockWrapper::AnyOf => todo!(),
+            BlockWrapper::Order(o) => o.get_input(start_from_left, render),
+            BlockWrapper::AnyOf(a) => a.get_input(start_from_left, render),
             BlockWrapper::OneOf(o) => o.get_input(start_from_left, render),
             BlockWrapper::Paragraph(p) => {
                 p.get_input(start_from_left, &mut |line| render(vec![line]))
@@ -53,10 +57,10 @@ impl BlockWrapper {
             BlockWrapper::Answered(_) => None,
         }
     }
-    pub fn as_lines(&self) -> Vec<Line> {
+    pub fn as_lines(&self) -> Vec<Line<'_>> {
         match self {
-            BlockWrapper::Order => todo!(),
-            BlockWrapper::AnyOf => todo!(),
+            BlockWrapper::Order(o) => o.as_lines(),
+            BlockWrapper::AnyOf(a) => a.as_lines(),
             BlockWrapper::OneOf(o) => o.as_lines(),
             BlockWrapper::Paragraph(p) => vec![p.as_line()],
             BlockWrapper::Answered(a) => a.as_lines(),
@@ -64,17 +68,22 @@ impl BlockWrapper {
     }
 }
 
+mod any_of_wrapper;
+mod list_cursor;
 mod one_of_wrapper;
+mod order_wrapper;
 pub mod paragraph_wrapper;
 mod answered_block_wrapper {
+    use answered_any_of_wrapper::AnsweredAnyOfWrapper;
     use answered_one_of_wrapper::AnsweredOneOfWrapper;
+    use answered_order_wrapper::AnsweredOrderWrapper;
     use answered_paragraph_wrapper::AnsweredParagraphWrapper;
     use ratatui::text::Line;
 
     #[derive(Debug)]
     pub enum AnsweredBlockWrapper {
-        Order,
-        AnyOf,
+        Order(AnsweredOrderWrapper),
+        AnyOf(AnsweredAnyOfWrapper),
         OneOf(AnsweredOneOfWrapper),
         Paragraph(AnsweredParagraphWrapper),
     }
@@ -82,15 +91,23 @@ mod answered_block_wrapper {
         fn from(value: s_text_input_f::BlockAnswered) -> Self {
             match value {
                 s_text_input_f::BlockAnswered::Order {
-                    items: _,
-                    user_answer: _,
-                    correct_answer: _,
-                } => todo!(),
+                    items,
+                    user_answer,
+                    correct_answer,
+                } => Self::Order(AnsweredOrderWrapper::new(
+                    items,
+                    user_answer,
+                    correct_answer,
+                )),
                 s_text_input_f::BlockAnswered::AnyOf {
-                    items: _,
-                    user_answer: _,
-                    correct_answer: _,
-                } => todo!(),
+                    items,
+                    user_answer,
+                    correct_answer,
+                } => Self::AnyOf(AnsweredAnyOfWrapper::new(
+                    items,
+                    user_answer,
+                    correct_answer,
+                )),
                 s_text_input_f::BlockAnswered::OneOf {
                     items,
                     user_answer,
@@ -108,10 +125,10 @@ mod answered_block_wrapper {
         }
     }
     impl AnsweredBlockWrapper {
-        pub fn as_lines(&self) -> Vec<Line> {
+        pub fn as_lines(&self) -> Vec<Line<'_>> {
             match self {
-                AnsweredBlockWrapper::Order => todo!(),
-                AnsweredBlockWrapper::AnyOf => todo!(),
+                AnsweredBlockWrapper::Order(x) => x.as_lines(),
+                AnsweredBlockWrapper::AnyOf(x) => x.as_lines(),
                 AnsweredBlockWrapper::OneOf(x) => x.as_lines(),
                 AnsweredBlockWrapper::Paragraph(x) => {
                     vec![x.as_line()]
@@ -120,6 +137,8 @@ mod answered_block_wrapper {
         }
     }
 
+    mod answered_any_of_wrapper;
+    mod answered_order_wrapper;
     mod answered_paragraph_wrapper;
     mod answered_one_of_wrapper {
         use ratatui::{
@@ -141,7 +160,7 @@ mod answered_block_wrapper {
                     correct_answer,
                 }
             }
-            pub fn as_lines(&self) -> Vec<Line> {
+            pub fn as_lines(&self) -> Vec<Line<'_>> {
                 let mut lines = self
                     .items
                     .iter()
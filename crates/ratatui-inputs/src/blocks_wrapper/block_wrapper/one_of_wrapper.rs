@@ -126,6 +126,7 @@ enum Event {
     PrevBlock,
     Redraw,
     Cancel,
+    Undo,
 }
 impl OneOfWrapper {
     #[allow(clippy::too_many_lines)]
@@ -154,6 +155,12 @@ impl OneOfWrapper {
                                         Some(Event::PrevItem)
                                     }
                                 }
+                                KeyCode::Char('z' | 'Z')
+                                    if k.modifiers
+                                        .contains(crossterm::event::KeyModifiers::CONTROL) =>
+                                {
+                                    Some(Event::Undo)
+                                }
                                 KeyCode::Esc | KeyCode::Char('q' | 'Q') => Some(Event::Cancel),
                                 _ => None,
                             }
@@ -231,6 +238,7 @@ impl OneOfWrapper {
             }
             Event::Redraw => None,
             Event::Cancel => Some(ResultKind::Canceled),
+            Event::Undo => Some(ResultKind::Undo),
         }
     }
 }
@@ -0,0 +1,256 @@
+use crossterm::event::{KeyCode, KeyEventKind};
+use rand::seq::SliceRandom;
+use ratatui::{
+    style::{Style, Stylize},
+    text::{Line, Span},
+};
+
+use super::list_cursor::ListCursor;
+use crate::ResultKind;
+
+#[derive(Debug)]
+pub struct OrderWrapper {
+    items: Vec<String>,
+    /// `order[position]` is starting position of item displayed at `position`
+    order: Vec<usize>,
+    cursor: ListCursor,
+    grabbed: bool,
+}
+impl From<Vec<String>> for OrderWrapper {
+    fn from(items: Vec<String>) -> Self {
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.shuffle(&mut rand::rng());
+        Self {
+            items,
+            order,
+            cursor: ListCursor::default(),
+            grabbed: false,
+        }
+    }
+}
+impl OrderWrapper {
+    pub fn finalize(self) -> Vec<String> {
+        self.order.into_iter().map(|x| x.to_string()).collect()
+    }
+    pub fn get_input(
+        &mut self,
+        start_from_left: bool,
+        render: &mut impl FnMut(Vec<Line>) -> std::io::Result<()>,
+    ) -> Option<std::io::Result<ResultKind>> {
+        if start_from_left {
+            self.cursor.select_first(self.items.len())?;
+        } else {
+            self.cursor.select_last(self.items.len())?;
+        }
+        let mut render = |order: &OrderWrapper| {
+            let mut lines = order.as_lines();
+            let style = if order.grabbed {
+                grabbed_line_styles()
+            } else {
+                current_line_styles()
+            };
+            lines[order.cursor.position()] =
+                lines[order.cursor.position()].to_owned().patch_style(style);
+            render(lines)
+        };
+        let result = (|| loop {
+            render(self)?;
+            if let Some(event) = self.handle_event(Self::get_event()?) {
+                break Ok(event);
+            }
+        })();
+        self.grabbed = false;
+        Some(result)
+    }
+    pub fn as_lines(&self) -> Vec<Line<'_>> {
+        self.order
+            .iter()
+            .enumerate()
+            .map(|(position, &item)| as_line(position, &self.items[item]))
+            .collect()
+    }
+}
+#[derive(Clone, Copy)]
+enum Event {
+    Grab,
+    NextItem,
+    PrevItem,
+    MoveItemDown,
+    MoveItemUp,
+    NextBlock,
+    EnterKey,
+    PrevBlock,
+    Redraw,
+    Cancel,
+    Undo,
+}
+impl OrderWrapper {
+    #[allow(clippy::too_many_lines)]
+    fn get_event() -> std::io::Result<Event> {
+        Ok({
+            loop {
+                if let Some(x) = match crossterm::event::read()? {
+                    crossterm::event::Event::Key(k) => {
+                        if k.kind == KeyEventKind::Press {
+                            match k.code {
+                                KeyCode::Char(' ') => Some(Event::Grab),
+                                KeyCode::Enter => Some(Event::EnterKey),
+                                KeyCode::Char('J') => Some(Event::MoveItemDown),
+                                KeyCode::Char('K') => Some(Event::MoveItemUp),
+                                KeyCode::Down | KeyCode::Char('j') => {
+                                    if k.modifiers.contains(crossterm::event::KeyModifiers::SHIFT) {
+                                        Some(Event::MoveItemDown)
+                                    } else {
+                                        Some(Event::NextItem)
+                                    }
+                                }
+                                KeyCode::Up | KeyCode::Char('k') => {
+                                    if k.modifiers.contains(crossterm::event::KeyModifiers::SHIFT) {
+                                        Some(Event::MoveItemUp)
+                                    } else {
+                                        Some(Event::PrevItem)
+                                    }
+                                }
+                                KeyCode::Tab => {
+                                    if cfg!(feature = "fast_tab_scroll") {
+                                        Some(Event::NextBlock)
+                                    } else {
+                                        Some(Event::NextItem)
+                                    }
+                                }
+                                KeyCode::BackTab => {
+                                    if cfg!(feature = "fast_tab_scroll") {
+                                        Some(Event::PrevBlock)
+                                    } else {
+                                        Some(Event::PrevItem)
+                                    }
+                                }
+                                KeyCode::Char('z' | 'Z')
+                                    if k.modifiers
+                                        .contains(crossterm::event::KeyModifiers::CONTROL) =>
+                                {
+                                    Some(Event::Undo)
+                                }
+                                KeyCode::Esc | KeyCode::Char('q' | 'Q') => Some(Event::Cancel),
+                                _ => None,
+                            }
+                        } else {
+                            None
+                        }
+                    }
+                    crossterm::event::Event::Resize(_, _) => Some(Event::Redraw),
+                    _ => None,
+                } {
+                    break x;
+                }
+            }
+        })
+    }
+    fn handle_event(&mut self, event: Event) -> Option<ResultKind> {
+        match event {
+            Event::Grab => {
+                self.grabbed = !self.grabbed;
+                None
+            }
+            Event::NextItem if self.grabbed => self.handle_event(Event::MoveItemDown),
+            Event::PrevItem if self.grabbed => self.handle_event(Event::MoveItemUp),
+            Event::NextItem => {
+                let already_last_elem = !self.cursor.select_next(self.items.len()).unwrap();
+                if already_last_elem {
+                    Some(ResultKind::NextBlock)
+                } else {
+                    None
+                }
+            }
+            Event::PrevItem => {
+                let already_first_elem = !self.cursor.select_prev(self.items.len()).unwrap();
+                if already_first_elem {
+                    Some(ResultKind::PrevBlock)
+                } else {
+                    None
+                }
+            }
+            Event::MoveItemDown => {
+                self.cursor.move_down(&mut self.order);
+                None
+            }
+            Event::MoveItemUp => {
+                self.cursor.move_up(&mut self.order);
+                None
+            }
+            Event::NextBlock => Some(ResultKind::NextBlock),
+            Event::PrevBlock => Some(ResultKind::PrevBlock),
+            Event::EnterKey => {
+                if self.grabbed {
+                    self.grabbed = false;
+                    None
+                } else {
+                    Some(ResultKind::Ok)
+                }
+            }
+            Event::Redraw => None,
+            Event::Cancel => Some(ResultKind::Canceled),
+            Event::Undo => Some(ResultKind::Undo),
+        }
+    }
+}
+fn as_line(position: usize, s: &str) -> Line<'_> {
+    Line::from(vec![
+        Span::raw(format!(" {}. ", position + 1)).blue(),
+        Span::raw(s),
+    ])
+    .italic()
+}
+fn current_line_styles() -> Style {
+    Style::new().bold().fg(ratatui::style::Color::Magenta)
+}
+fn grabbed_line_styles() -> Style {
+    Style::new()
+        .bold()
+        .not_italic()
+        .fg(ratatui::style::Color::Yellow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrapper(order: Vec<usize>) -> OrderWrapper {
+        OrderWrapper {
+            items: order.iter().map(|x| format!("item {x}")).collect(),
+            order,
+            cursor: ListCursor::default(),
+            grabbed: false,
+        }
+    }
+
+    #[test]
+    fn finalize_maps_display_order_to_item_indices() {
+        let order = wrapper(vec![2, 0, 1]);
+        assert_eq!(order.finalize(), ["2", "0", "1"]);
+    }
+
+    #[test]
+    fn finalize_follows_moved_items() {
+        let mut order = wrapper(vec![0, 1, 2]);
+        order.handle_event(Event::Grab);
+        order.handle_event(Event::NextItem);
+        order.handle_event(Event::NextItem);
+        order.handle_event(Event::EnterKey);
+        order.handle_event(Event::PrevItem);
+        order.handle_event(Event::MoveItemUp);
+        assert_eq!(order.finalize(), ["2", "1", "0"]);
+    }
+
+    #[test]
+    fn shuffled_order_is_permutation() {
+        let order = OrderWrapper::from((0..10).map(|x| x.to_string()).collect::<Vec<_>>());
+        let mut indices = order
+            .finalize()
+            .iter()
+            .map(|x| x.parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        assert_eq!(indices, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -47,6 +47,7 @@ impl ParagraphWrapper {
                         }
                     }
                     ResultKind::Canceled => break ResultKind::Canceled,
+                    ResultKind::Undo => break ResultKind::Undo,
                     ResultKind::NextBlock => {
                         let next_elem_exist = self.select_next_placeholder().unwrap();
                         if !next_elem_exist {
@@ -61,6 +61,7 @@ impl BlocksWrapper {
                         }
                     }
                     ResultKind::Canceled => break ResultKind::Canceled,
+                    ResultKind::Undo => break ResultKind::Undo,
                     ResultKind::NextBlock => {
                         self.select_next_block().unwrap();
                     }
@@ -47,6 +47,8 @@ pub enum ResultKind {
     Canceled,
     NextBlock,
     PrevBlock,
+    /// Ctrl+Z, user wants to undo previous action.
+    Undo,
 }
 
 mod blank_field;
@@ -63,15 +65,22 @@ mod blocks_wrapper;
 pub fn get_text_input(
     render: &mut impl FnMut(ratatui::text::Text, String) -> std::io::Result<()>,
 ) -> std::io::Result<(ResultKind, String)> {
-    let mut multyline_input = multiline_input::MultilineInput::default();
+    edit_text("", render)
+}
+
+/// Same as [`get_text_input`], but input starts with `initial` text.
+pub fn edit_text(
+    initial: &str,
+    render: &mut impl FnMut(ratatui::text::Text, String) -> std::io::Result<()>,
+) -> std::io::Result<(ResultKind, String)> {
+    let mut multyline_input = multiline_input::MultilineInput::new(initial);
     loop {
         match multyline_input.get_input(&mut |x| render(x.style(), x.text()))? {
             ResultKind::Ok => return Ok((ResultKind::Ok, multyline_input.text().to_owned())),
             ResultKind::Canceled => {
                 return Ok((ResultKind::Canceled, multyline_input.text().to_owned()));
             }
-            ResultKind::NextBlock => (),
-            ResultKind::PrevBlock => (),
+            ResultKind::NextBlock | ResultKind::PrevBlock | ResultKind::Undo => (),
         }
     }
 }
@@ -109,7 +118,16 @@ pub fn get_block(
 pub fn get_blocks(
     render: &mut impl FnMut(Text, String) -> std::io::Result<()>,
 ) -> std::io::Result<Option<s_text_input_f::BlocksWithAnswer>> {
-    let (result_kind, inputs) = get_text_input(&mut |styled, text| {
+    edit_blocks("", render)
+}
+
+/// Same as [`get_blocks`], but editor is filled with `initial` source, e.g. rendered by
+/// [`s_text_input_f_parser::to_source`].
+pub fn edit_blocks(
+    initial: &str,
+    render: &mut impl FnMut(Text, String) -> std::io::Result<()>,
+) -> std::io::Result<Option<s_text_input_f::BlocksWithAnswer>> {
+    let (result_kind, inputs) = edit_text(initial, &mut |styled, text| {
         let support_text = s_text_input_f_parser::parse_blocks(text.trim())
             .map(|parsed| {
                 let mut buffer = String::new();
@@ -29,6 +29,14 @@ enum Event {
 }
 
 impl MultilineInput {
+    /// Input with `text`, cursor is at the end.
+    pub fn new(text: &str) -> Self {
+        let text = text.chars().collect::<Vec<_>>();
+        Self {
+            cursor: text.len(),
+            text,
+        }
+    }
     pub fn text(&self) -> String {
         self.text.iter().collect()
     }
@@ -1,18 +1,15 @@
 use chumsky::prelude::*;
 
+use crate::line;
+
 pub struct CorrectAnyOf {
     pub variants: Vec<String>,
     pub correct: Vec<usize>,
 }
 
 pub(crate) fn any_of_parser() -> impl Parser<char, CorrectAnyOf, Error = Simple<char>> {
-    let eol = || choice((just('\n').ignored(), end().ignored()));
-    let variant = || {
-        take_until(eol())
-            .map(|(content, ())| content.into_iter().collect::<String>().trim().to_owned())
-    };
-    let correct_variant = || just("- [x] ").ignore_then(variant()).map(|x| (true, x));
-    let wrong_variant = || just("- [ ] ").ignore_then(variant()).map(|x| (false, x));
+    let correct_variant = || just("- [x] ").ignore_then(line()).map(|x| (true, x));
+    let wrong_variant = || just("- [ ] ").ignore_then(line()).map(|x| (false, x));
     let variant = || choice((correct_variant(), wrong_variant()));
 
     variant().repeated().at_least(1).map(|lines| {
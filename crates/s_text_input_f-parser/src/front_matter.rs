@@ -0,0 +1,68 @@
+use chumsky::prelude::*;
+
+/// Line, that opens and closes front matter. It differs from `---`, that separates tasks in
+/// markdown files.
+pub const DELIMITER: &str = "+++";
+
+/// Metadata of task, written before its blocks:
+///
+/// ```text
+/// +++
+/// tags: rust::lifetimes, borrowing
+/// +++
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FrontMatter {
+    /// Without duplicates, in order of appearance.
+    pub tags: Vec<String>,
+}
+
+impl FrontMatter {
+    /// Renders front matter back to source. Empty front matter is rendered as empty string.
+    #[must_use]
+    pub fn to_source(&self) -> String {
+        if self.tags.is_empty() {
+            String::new()
+        } else {
+            format!("{DELIMITER}\ntags: {}\n{DELIMITER}\n", self.tags.join(", "))
+        }
+    }
+}
+
+/// Tag levels separated by `::`. Levels can't contain whitespace, commas and colons.
+pub(crate) fn tag_parser() -> impl Parser<char, String, Error = Simple<char>> + Clone {
+    filter(|&ch: &char| !ch.is_whitespace() && ch != ',' && ch != ':')
+        .repeated()
+        .at_least(1)
+        .collect::<String>()
+        .separated_by(just("::"))
+        .at_least(1)
+        .map(|levels| levels.join("::"))
+}
+
+pub(crate) fn front_matter_parser() -> impl Parser<char, FrontMatter, Error = Simple<char>> {
+    let space = one_of(" \t").repeated();
+    let tags = just("tags:")
+        .ignore_then(
+            tag_parser()
+                .padded_by(space.clone())
+                .separated_by(just(','))
+                .allow_trailing(),
+        )
+        .then_ignore(space)
+        .then_ignore(just('\n'));
+    just(DELIMITER)
+        .then(just('\n'))
+        .ignore_then(tags.repeated())
+        .then_ignore(just(DELIMITER))
+        .then_ignore(choice((just('\n').ignored(), end())))
+        .map(|lines| {
+            let mut tags = Vec::new();
+            for tag in lines.into_iter().flatten() {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            FrontMatter { tags }
+        })
+}
@@ -1,4 +1,5 @@
 use chumsky::prelude::*;
+use front_matter::FrontMatter;
 use s_text_input_f::{Block, BlocksWithAnswer};
 
 impl FromIterator<CorrectBlock> for BlocksWithAnswer {
@@ -11,7 +12,7 @@ impl FromIterator<CorrectBlock> for BlocksWithAnswer {
 /// Represents a parsed interactive block along with its correct answers
 #[derive(Debug)]
 pub struct CorrectBlock {
-    /// The interactive block content (Paragraph, OneOf, or AnyOf)
+    /// The interactive block content (Paragraph, OneOf, AnyOf, or Order)
     pub block: Block,
     /// The correct answers for this block
     pub answer: Vec<String>,
@@ -40,6 +41,14 @@ impl From<any_of::CorrectAnyOf> for CorrectBlock {
         }
     }
 }
+impl From<order::CorrectOrder> for CorrectBlock {
+    fn from(value: order::CorrectOrder) -> Self {
+        Self {
+            block: Block::Order(value.items),
+            answer: value.correct.into_iter().map(|x| x.to_string()).collect(),
+        }
+    }
+}
 
 /// Parses a paragraph with placeholders marked by backticks
 ///
@@ -85,9 +94,67 @@ pub fn parse_any_of(input: &str) -> Result<any_of::CorrectAnyOf, Vec<Simple<char
     any_of::any_of_parser().then_ignore(end()).parse(input)
 }
 
+/// Parses an ordering question written as numbered list
+///
+/// Numbers are correct positions (starting from 1) of items, while items are stored in the order
+/// they are written.
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f_parser::parse_order;
+///
+/// let result = parse_order("2. Second\n3. Third\n1. First").unwrap();
+/// assert_eq!(result.items, vec!["Second", "Third", "First"]);
+/// assert_eq!(result.correct, vec![2, 0, 1]);
+///
+/// let in_correct_order = result
+///     .correct
+///     .iter()
+///     .map(|&i| result.items[i].as_str())
+///     .collect::<Vec<_>>();
+/// assert_eq!(in_correct_order, vec!["First", "Second", "Third"]);
+///
+/// assert!(parse_order("1. First\n1. Second").is_err());
+/// assert!(parse_order("1. First\n3. Second").is_err());
+/// ```
+pub fn parse_order(input: &str) -> Result<order::CorrectOrder, Vec<Simple<char>>> {
+    order::order_parser().then_ignore(end()).parse(input)
+}
+
 pub mod any_of;
+pub mod front_matter;
 pub mod one_of;
+pub mod order;
 pub mod paragraph;
+mod to_source;
+pub use to_source::to_source;
+
+/// Backslash escape: ASCII punctuation after backslash is taken literally and backslash before
+/// line break continues the line. Other backslashes are kept as is.
+fn escaped() -> impl Parser<char, char, Error = Simple<char>> + Clone {
+    just('\\').ignore_then(filter(|&ch: &char| ch.is_ascii_punctuation() || ch == '\n'))
+}
+
+/// Rest of line with escapes, without surrounding whitespace.
+fn line() -> impl Parser<char, String, Error = Simple<char>> + Clone {
+    line_without(&[])
+}
+
+/// Rest of line, that has no unescaped `excluded` characters.
+fn line_without(
+    excluded: &'static [char],
+) -> impl Parser<char, String, Error = Simple<char>> + Clone {
+    choice((
+        escaped(),
+        filter(move |ch: &char| *ch != '\n' && *ch != '\\' && !excluded.contains(ch)),
+        just('\\'),
+    ))
+    .repeated()
+    .collect::<String>()
+    .then_ignore(choice((just('\n').ignored(), end())))
+    .map(|content| content.trim().to_owned())
+}
 
 /// Parses a single block of any supported type
 ///
@@ -98,18 +165,41 @@ pub mod paragraph;
 ///
 /// let result = parse_block("hello `world`!").unwrap();
 /// assert_eq!(result.answer, vec!["world"]);
+///
+/// let result = parse_block("1. First\n2. Second").unwrap();
+/// assert!(matches!(result.block, s_text_input_f::Block::Order(_)));
+/// assert_eq!(result.answer, vec!["0", "1"]);
+/// ```
+///
+/// Numbered lines with placeholders are paragraphs, not order:
+///
+/// ```
+/// use s_text_input_f::Block;
+/// use s_text_input_f_parser::{parse_block, parse_blocks};
+///
+/// let result = parse_block("1. Buy `milk`").unwrap();
+/// assert!(matches!(result.block, Block::Paragraph(_)));
+/// assert!(parse_block("1. Buy `milk`\n2. Then `eggs`").is_err());
+///
+/// let result = parse_blocks("1. Buy `milk`\n\n2. Then `eggs`").unwrap();
+/// assert!(matches!(result.blocks[..], [Block::Paragraph(_), Block::Paragraph(_)]));
+/// assert_eq!(result.answer, [["milk"], ["eggs"]]);
 /// ```
 pub fn parse_block(input: &str) -> Result<CorrectBlock, Vec<Simple<char>>> {
     block_parser().then_ignore(end()).parse(input)
 }
 
 fn block_parser() -> impl Parser<char, CorrectBlock, Error = Simple<char>> {
+    // Numbered lines are checked after choice, otherwise error about their positions would be
+    // replaced by errors of other alternatives.
     choice((
-        any_of::any_of_parser().map(CorrectBlock::from),
-        one_of::one_of_parser().map(CorrectBlock::from),
-        paragraph::paragraph_parser().map(CorrectBlock::from),
-        // TODO: Order
+        any_of::any_of_parser().map(|x| Ok(x.into())),
+        one_of::one_of_parser().map(|x| Ok(x.into())),
+        order::numbered_lines()
+            .map_with_span(|lines, span| order::to_order(lines, span).map(CorrectBlock::from)),
+        paragraph::paragraph_parser().map(|x| Ok(x.into())),
     ))
+    .try_map(|block, _| block)
 }
 
 /// Parses a complete document containing multiple blocks
@@ -128,6 +218,65 @@ fn block_parser() -> impl Parser<char, CorrectBlock, Error = Simple<char>> {
 pub fn parse_blocks(input: &str) -> Result<BlocksWithAnswer, Vec<Simple<char>>> {
     blocks_parser().then_ignore(end()).parse(input)
 }
+/// Task with its metadata.
+#[derive(Debug)]
+pub struct CorrectTask {
+    pub front_matter: FrontMatter,
+    pub blocks: BlocksWithAnswer,
+}
+
+/// Parses blocks, that can be preceded by [front matter](front_matter::FrontMatter)
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f_parser::parse_task;
+///
+/// let result = parse_task("+++\ntags: rust::lifetimes, cs\n+++\n\n'a is `lifetime`").unwrap();
+/// assert_eq!(result.front_matter.tags, ["rust::lifetimes", "cs"]);
+/// assert_eq!(result.blocks.answer, [vec!["lifetime".to_owned()]]);
+///
+/// let result = parse_task("no `tags`").unwrap();
+/// assert!(result.front_matter.tags.is_empty());
+///
+/// assert!(parse_task("+++\ntags: two words\n+++\ntask `a`").is_err());
+/// ```
+pub fn parse_task(input: &str) -> Result<CorrectTask, Vec<Simple<char>>> {
+    // Otherwise invalid front matter would be silently parsed as paragraphs.
+    let has_front_matter = input
+        .lines()
+        .next()
+        .is_some_and(|line| line.trim_end() == front_matter::DELIMITER);
+    let (front_matter, blocks) = if has_front_matter {
+        front_matter::front_matter_parser()
+            .then_ignore(just('\n').repeated())
+            .then(blocks_parser())
+            .then_ignore(end())
+            .parse(input)?
+    } else {
+        (FrontMatter::default(), parse_blocks(input)?)
+    };
+    Ok(CorrectTask {
+        front_matter,
+        blocks,
+    })
+}
+
+/// Parses tag, which levels are separated by `::`
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f_parser::parse_tag;
+///
+/// assert_eq!(parse_tag("rust::lifetimes").unwrap(), "rust::lifetimes");
+/// assert!(parse_tag("rust::").is_err());
+/// assert!(parse_tag("two words").is_err());
+/// ```
+pub fn parse_tag(input: &str) -> Result<String, Vec<Simple<char>>> {
+    front_matter::tag_parser().then_ignore(end()).parse(input)
+}
+
 fn blocks_parser() -> impl Parser<char, BlocksWithAnswer, Error = Simple<char>> {
     block_parser()
         .separated_by(just('\n').repeated().at_least(1))
@@ -39,6 +39,10 @@ consequence - a `result` of a particular action or situation, often one that is
 - not correct
 - not correct
 
+2. second step
+1. first step
+3. third step
+
 - not one_of
     "#.trim());
     // test("hello `world`!");
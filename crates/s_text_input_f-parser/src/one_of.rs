@@ -1,18 +1,15 @@
 use chumsky::prelude::*;
 
+use crate::line;
+
 pub struct CorrectOneOf {
     pub variants: Vec<String>,
     pub correct: usize,
 }
 
 pub(crate) fn one_of_parser() -> impl Parser<char, CorrectOneOf, Error = Simple<char>> {
-    let eol = || choice((just('\n').ignored(), end().ignored()));
-    let variant = || {
-        take_until(eol())
-            .map(|(content, ())| content.into_iter().collect::<String>().trim().to_owned())
-    };
-    let correct_variant = || just("* ").ignore_then(variant());
-    let wrong_variant = || just("- ").ignore_then(variant());
+    let correct_variant = || just("* ").ignore_then(line());
+    let wrong_variant = || just("- ").ignore_then(line());
     wrong_variant()
         .repeated()
         .then(correct_variant())
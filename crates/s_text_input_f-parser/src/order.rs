@@ -0,0 +1,103 @@
+use chumsky::prelude::*;
+
+use crate::line_without;
+
+pub struct CorrectOrder {
+    /// Items in the same order as they are written
+    pub items: Vec<String>,
+    /// `correct[position]` is index of item, that must be at `position`
+    pub correct: Vec<usize>,
+}
+
+pub(crate) fn order_parser() -> impl Parser<char, CorrectOrder, Error = Simple<char>> {
+    numbered_lines().try_map(to_order)
+}
+
+/// Two or more numbered lines, that are order block even if their positions are wrong.
+pub(crate) fn numbered_lines() -> impl Parser<char, Vec<(usize, String)>, Error = Simple<char>> {
+    let position = text::int(10).try_map(|x: String, span| {
+        x.parse::<usize>()
+            .map_err(|err| Simple::custom(span, format!("invalid position: {err}")))
+    });
+    // Backtick would be placeholder, so such lines are left for paragraph.
+    let numbered_item = position.then_ignore(just(". ")).then(line_without(&['`']));
+
+    numbered_item.repeated().at_least(2)
+}
+
+/// # Errors
+/// if positions aren't permutation of `1..=lines.len()`
+pub(crate) fn to_order(
+    lines: Vec<(usize, String)>,
+    span: std::ops::Range<usize>,
+) -> Result<CorrectOrder, Simple<char>> {
+    let mut correct = vec![None; lines.len()];
+    for (index, (position, _)) in lines.iter().enumerate() {
+        let slot = position
+            .checked_sub(1)
+            .and_then(|position| correct.get_mut(position))
+            .ok_or_else(|| {
+                Simple::custom(
+                    span.clone(),
+                    format!("position {position} is out of range 1..={}", lines.len()),
+                )
+            })?;
+        if slot.replace(index).is_some() {
+            return Err(Simple::custom(
+                span,
+                format!("position {position} is used twice"),
+            ));
+        }
+    }
+    let correct = correct.into_iter().map(Option::unwrap).collect();
+    let items = lines.into_iter().map(|(_, content)| content).collect();
+    Ok(CorrectOrder { items, correct })
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::error::SimpleReason;
+    use s_text_input_f::Block;
+
+    use crate::{parse_blocks, to_source};
+
+    #[test]
+    fn keeps_written_item_order() {
+        let source = "3. c\n1. a\n2. b";
+        let blocks = parse_blocks(source).unwrap();
+        assert_eq!(
+            blocks.blocks,
+            [Block::Order(vec!["c".into(), "a".into(), "b".into()])]
+        );
+        assert_eq!(blocks.answer, [["1", "2", "0"]]);
+    }
+
+    #[test]
+    fn reports_wrong_positions_inside_document() {
+        for (source, message) in [
+            ("1. a\n1. b", "position 1 is used twice"),
+            (
+                "hello `world`\n\n1. a\n3. b",
+                "position 3 is out of range 1..=2",
+            ),
+        ] {
+            let errors = parse_blocks(source).unwrap_err();
+            assert!(
+                matches!(&errors[..], [error] if error.reason() == &SimpleReason::Custom(message.into())),
+                "{errors:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_through_source() {
+        let source = "2. \\`b\\`\n1. a\n\nhello `world`";
+        let blocks = parse_blocks(source).unwrap();
+        assert_eq!(
+            blocks.blocks[0],
+            Block::Order(vec!["`b`".into(), "a".into()])
+        );
+        assert_eq!(to_source(&blocks), source);
+        assert_eq!(parse_blocks(&to_source(&blocks)).unwrap(), blocks);
+    }
+}
@@ -1,6 +1,8 @@
 use chumsky::prelude::*;
 use s_text_input_f::{Paragraph, ParagraphItem};
 
+use crate::escaped;
+
 #[derive(Debug)]
 pub(crate) struct CorrectParagraphItem {
     pub(crate) input: ParagraphItem,
@@ -24,25 +26,26 @@ impl FromIterator<CorrectParagraphItem> for CorrectParagraph {
     }
 }
 
+fn is_printable(ch: char) -> bool {
+    ch.is_whitespace() || !ch.is_control()
+}
+
 pub(crate) fn paragraph_parser() -> impl Parser<char, CorrectParagraph, Error = Simple<char>> {
-    let text = filter::<_, _, Simple<char>>(|&ch| {
-        ch != '`'
-            && ch != '\n'
-            && (char::is_alphanumeric(ch)
-                || char::is_whitespace(ch)
-                || char::is_ascii_punctuation(&ch))
-    })
+    let text = choice((
+        escaped(),
+        filter(|&ch| ch != '`' && ch != '\n' && ch != '\\' && is_printable(ch)),
+        just('\\'),
+    ))
     .repeated()
     .at_least(1)
     .collect::<String>();
     let input_field = just('`')
         .ignore_then(
-            filter(|&ch| {
-                ch != '`'
-                    && (char::is_alphanumeric(ch)
-                        || char::is_whitespace(ch)
-                        || char::is_ascii_punctuation(&ch))
-            })
+            choice((
+                escaped(),
+                filter(|&ch| ch != '`' && ch != '\\' && is_printable(ch)),
+                just('\\'),
+            ))
             .repeated()
             .collect::<String>(),
         )
@@ -0,0 +1,125 @@
+use s_text_input_f::{Block, BlocksWithAnswer, ParagraphItem};
+
+/// Escapes backslashes and line breaks, plus `special` characters.
+fn escape(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if ch == '\\' || ch == '\n' || special.contains(&ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Escapes list item. Leading `[` would turn choice into checkbox.
+fn escape_item(item: &str) -> String {
+    let escaped = escape(item, &[]);
+    match escaped.strip_prefix('[') {
+        Some(rest) => format!("\\[{rest}"),
+        None => escaped,
+    }
+}
+
+fn paragraph(items: &[ParagraphItem], answer: &[String]) -> String {
+    let mut answer = answer.iter();
+    let mut source = items
+        .iter()
+        .map(|item| match item {
+            ParagraphItem::Text(text) => escape(text, &['`']),
+            ParagraphItem::Placeholder => {
+                format!(
+                    "`{}`",
+                    escape(answer.next().map_or("", String::as_str), &['`'])
+                )
+            }
+        })
+        .collect::<String>();
+    // Paragraph must not look like list.
+    if source.starts_with(['-', '*']) {
+        source.insert(0, '\\');
+    } else if let Some(dot) = source.find(|ch: char| !ch.is_ascii_digit())
+        && dot > 0
+        && source[dot..].starts_with('.')
+    {
+        source.insert(dot, '\\');
+    }
+    source
+}
+
+fn parse_positions(answer: &[String]) -> impl Iterator<Item = usize> {
+    answer.iter().filter_map(|x| x.parse().ok())
+}
+
+fn block(block: &Block, answer: &[String]) -> Option<String> {
+    let lines = match block {
+        Block::Paragraph(items) => return Some(paragraph(items, answer)),
+        Block::OneOf(variants) => {
+            let correct = parse_positions(answer).next();
+            variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| {
+                    let marker = if Some(i) == correct { '*' } else { '-' };
+                    format!("{marker} {}", escape_item(variant))
+                })
+                .collect::<Vec<_>>()
+        }
+        Block::AnyOf(variants) => {
+            let correct = parse_positions(answer).collect::<Vec<_>>();
+            variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| {
+                    let marker = if correct.contains(&i) { 'x' } else { ' ' };
+                    format!("- [{marker}] {}", escape_item(variant))
+                })
+                .collect()
+        }
+        Block::Order(items) => {
+            let correct = parse_positions(answer).collect::<Vec<_>>();
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let position = correct.iter().position(|&x| x == i).unwrap_or(i);
+                    format!("{}. {}", position + 1, escape(item, &['`']))
+                })
+                .collect()
+        }
+        _ => return None,
+    };
+    Some(lines.join("\n"))
+}
+
+/// Renders blocks back to the syntax of [`parse_blocks`](crate::parse_blocks).
+///
+/// # Escaping
+/// Backslash before ASCII punctuation means the character itself, backslash before line break
+/// continues the line. So `` ` `` in paragraphs and `\` everywhere are written as `` \` `` and
+/// `\\`, line breaks as `\` at the end of line. Paragraph starting with list marker and choice
+/// starting with `[` get their first character escaped.
+///
+/// Result is parsed back to `blocks`, if they could be produced by parser: paragraphs have no
+/// adjacent or empty texts, list items have no surrounding whitespace and there are no
+/// control characters other than whitespace. [`Block::Answered`] has no syntax and is skipped.
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f_parser::{parse_blocks, to_source};
+///
+/// let source = "`fn` main() {}\n\n- [x] \\[x] is checked\n- [ ] a\\\\b\n\n2. second\n1. first";
+/// let blocks = parse_blocks(source).unwrap();
+/// assert_eq!(to_source(&blocks), source);
+/// ```
+#[must_use]
+pub fn to_source(blocks: &BlocksWithAnswer) -> String {
+    blocks
+        .blocks
+        .iter()
+        .zip(&blocks.answer)
+        .filter_map(|(x, answer)| block(x, answer))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -0,0 +1,79 @@
+use proptest::{collection::vec, prelude::*, sample::subsequence};
+use s_text_input_f::{Block, BlocksWithAnswer, ParagraphItem};
+use s_text_input_f_parser::{parse_blocks, to_source};
+
+/// Text with characters, that need escaping.
+fn text() -> impl Strategy<Value = String> {
+    "[a-c0-9 .`\\\\*\\-\\[\\]\n—é]{0,6}"
+}
+
+/// List items have no surrounding whitespace after parsing.
+fn item() -> impl Strategy<Value = String> {
+    text().prop_map(|x| x.trim().to_owned())
+}
+
+/// Paragraph has no adjacent or empty texts after parsing.
+fn paragraph() -> impl Strategy<Value = (Block, Vec<String>)> {
+    vec((text(), prop::option::of(text())), 1..4).prop_map(|parts| {
+        let mut blocks = Vec::new();
+        let mut answer = Vec::new();
+        for (text, placeholder) in parts {
+            match blocks.last_mut() {
+                _ if text.is_empty() => (),
+                Some(ParagraphItem::Text(last)) => last.push_str(&text),
+                _ => blocks.push(ParagraphItem::Text(text)),
+            }
+            if let Some(x) = placeholder {
+                blocks.push(ParagraphItem::Placeholder);
+                answer.push(x);
+            }
+        }
+        if blocks.is_empty() {
+            blocks.push(ParagraphItem::Placeholder);
+            answer.push(String::new());
+        }
+        (Block::Paragraph(blocks), answer)
+    })
+}
+
+fn one_of() -> impl Strategy<Value = (Block, Vec<String>)> {
+    (vec(item(), 1..4), any::<prop::sample::Index>()).prop_map(|(items, i)| {
+        let correct = i.index(items.len()).to_string();
+        (Block::OneOf(items), vec![correct])
+    })
+}
+
+fn any_of() -> impl Strategy<Value = (Block, Vec<String>)> {
+    vec(item(), 1..4).prop_flat_map(|items| {
+        let len = items.len();
+        subsequence((0..len).collect::<Vec<_>>(), 0..=len).prop_map(move |correct| {
+            let correct = correct.iter().map(ToString::to_string).collect();
+            (Block::AnyOf(items.clone()), correct)
+        })
+    })
+}
+
+fn order() -> impl Strategy<Value = (Block, Vec<String>)> {
+    vec(item(), 2..5).prop_flat_map(|items| {
+        let positions = (0..items.len()).collect::<Vec<_>>();
+        Just(positions).prop_shuffle().prop_map(move |correct| {
+            let correct = correct.iter().map(ToString::to_string).collect();
+            (Block::Order(items.clone()), correct)
+        })
+    })
+}
+
+fn blocks() -> impl Strategy<Value = BlocksWithAnswer> {
+    vec(prop_oneof![paragraph(), one_of(), any_of(), order()], 1..4).prop_map(|blocks| {
+        let (blocks, answer) = blocks.into_iter().unzip();
+        BlocksWithAnswer { blocks, answer }
+    })
+}
+
+proptest! {
+    #[test]
+    fn parsed_blocks_survive_round_trip(blocks in blocks()) {
+        let source = to_source(&blocks);
+        prop_assert_eq!(parse_blocks(&source).ok(), Some(blocks), "{:?}", source);
+    }
+}
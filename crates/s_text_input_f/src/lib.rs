@@ -5,13 +5,13 @@ use std::{collections::BTreeSet, num::ParseIntError};
 use serde::{Deserialize, Serialize};
 
 pub type Blocks = Vec<Block>;
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BlocksWithAnswer {
     pub blocks: Blocks,
     pub answer: Response,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Block {
     /// # Response
@@ -41,7 +41,7 @@ impl Block {
 }
 
 pub type Paragraph = Vec<ParagraphItem>;
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum ParagraphItem {
     Text(String),
     Placeholder,
@@ -77,25 +77,182 @@ fn eq_response_item(
     if a.len() != b.len() {
         return false;
     }
-    a.iter().zip(b).all(|(a, b)| {
-        if trim {
-            let (a, b) = (a.trim(), b.trim());
-            if case_insensitive {
-                let (a, b) = (a.to_lowercase(), b.to_lowercase());
-                a == b
-            } else {
-                a == b
-            }
+    a.iter()
+        .zip(b)
+        .all(|(a, b)| eq_answer(a, b, trim, case_insensitive))
+}
+fn eq_answer(a: &str, b: &str, trim: bool, case_insensitive: bool) -> bool {
+    if trim {
+        let (a, b) = (a.trim(), b.trim());
+        if case_insensitive {
+            let (a, b) = (a.to_lowercase(), b.to_lowercase());
+            a == b
         } else {
-            #[allow(clippy::collapsible_if)]
-            if case_insensitive {
-                let (a, b) = (a.to_lowercase(), b.to_lowercase());
-                a == b
+            a == b
+        }
+    } else {
+        #[allow(clippy::collapsible_if)]
+        if case_insensitive {
+            let (a, b) = (a.to_lowercase(), b.to_lowercase());
+            a == b
+        } else {
+            a == b
+        }
+    }
+}
+
+/// Grades every block of response.
+///
+/// See [`grade_block`] for details.
+///
+/// # Panics
+/// Panics if the lengths of `blocks`, `user_answers` and `correct_answers` differ.
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f::{Block, ParagraphItem, grade_response};
+///
+/// let blocks = vec![
+///     Block::Paragraph(vec![ParagraphItem::Placeholder, ParagraphItem::Placeholder]),
+///     Block::one_of(["a", "b"]),
+/// ];
+/// let correct = vec![vec!["x".into(), "y".into()], vec!["1".into()]];
+/// let user = vec![vec!["x".into(), "z".into()], vec!["1".into()]];
+/// assert_eq!(grade_response(&blocks, &user, &correct, true, false), vec![0.5, 1.0]);
+/// ```
+#[must_use]
+pub fn grade_response(
+    blocks: &Blocks,
+    user_answers: &Response,
+    correct_answers: &Response,
+    trim: bool,
+    case_insensitive: bool,
+) -> Vec<f64> {
+    assert_eq!(blocks.len(), user_answers.len());
+    assert_eq!(blocks.len(), correct_answers.len());
+    blocks
+        .iter()
+        .zip(user_answers.iter().zip(correct_answers))
+        .map(|(block, (user_answer, correct_answer))| {
+            grade_block(block, user_answer, correct_answer, trim, case_insensitive)
+        })
+        .collect()
+}
+
+/// Grades single block.
+///
+/// # Returns
+/// Score from `0.0`(completely wrong) to `1.0`(completely correct):
+/// - `Order` - `1 - normalized Kendall tau distance`, so one swap of neighbours costs little
+/// - `AnyOf` - F1 score (harmonic mean of precision and recall)
+/// - `OneOf` - `1.0` if correct, `0.0` otherwise
+/// - `Paragraph` - share of correctly filled placeholders
+/// - `Answered` - always `1.0`, because there is nothing to answer
+///
+/// Unparsable answer is scored `0.0`.
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f::{Block, grade_block};
+///
+/// let order = Block::Order(vec!["a".into(), "b".into(), "c".into()]);
+/// let correct = vec!["0".into(), "1".into(), "2".into()];
+/// let user = vec!["1".into(), "0".into(), "2".into()];
+/// assert!((grade_block(&order, &user, &correct, true, false) - 2. / 3.).abs() < 1e-9);
+///
+/// let any_of = Block::AnyOf(vec!["a".into(), "b".into(), "c".into()]);
+/// let correct = vec!["0".into(), "1".into()];
+/// let user = vec!["0".into()];
+/// assert!((grade_block(&any_of, &user, &correct, true, false) - 2. / 3.).abs() < 1e-9);
+/// ```
+#[must_use]
+pub fn grade_block(
+    block: &Block,
+    user_answer: &ResponseItem,
+    correct_answer: &ResponseItem,
+    trim: bool,
+    case_insensitive: bool,
+) -> f64 {
+    match block {
+        Block::Order(_) => {
+            let (Ok(user_answer), Ok(correct_answer)) = (
+                try_parse_positions(user_answer),
+                try_parse_positions(correct_answer),
+            ) else {
+                return 0.0;
+            };
+            grade_order(&user_answer, &correct_answer)
+        }
+        Block::AnyOf(_) => {
+            let (Ok(user_answer), Ok(correct_answer)) = (
+                response_as_any_of(user_answer.clone()),
+                response_as_any_of(correct_answer.clone()),
+            ) else {
+                return 0.0;
+            };
+            grade_any_of(&user_answer, &correct_answer)
+        }
+        Block::OneOf(_) => {
+            if eq_response_item(user_answer, correct_answer, true, false) {
+                1.0
             } else {
-                a == b
+                0.0
+            }
+        }
+        Block::Paragraph(_) => {
+            if correct_answer.is_empty() {
+                return 1.0;
             }
+            let correct = correct_answer
+                .iter()
+                .zip(user_answer)
+                .filter(|(correct, user)| eq_answer(correct, user, trim, case_insensitive))
+                .count();
+            #[allow(clippy::cast_precision_loss)]
+            let score = correct as f64 / correct_answer.len() as f64;
+            score
         }
-    })
+        Block::Answered(_) => 1.0,
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn grade_order(user_answer: &[usize], correct_answer: &[usize]) -> f64 {
+    if user_answer.len() != correct_answer.len() {
+        return 0.0;
+    }
+    let Some(ranks) = user_answer
+        .iter()
+        .map(|item| correct_answer.iter().position(|x| x == item))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return 0.0;
+    };
+    let pairs = ranks.len() * ranks.len().saturating_sub(1) / 2;
+    if pairs == 0 {
+        return 1.0;
+    }
+    let discordant = (0..ranks.len())
+        .flat_map(|i| (i + 1..ranks.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| ranks[i] > ranks[j])
+        .count();
+    1.0 - discordant as f64 / pairs as f64
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn grade_any_of(user_answer: &BTreeSet<usize>, correct_answer: &BTreeSet<usize>) -> f64 {
+    if user_answer.is_empty() && correct_answer.is_empty() {
+        return 1.0;
+    }
+    let true_positives = user_answer.intersection(correct_answer).count();
+    if true_positives == 0 {
+        return 0.0;
+    }
+    let precision = true_positives as f64 / user_answer.len() as f64;
+    let recall = true_positives as f64 / correct_answer.len() as f64;
+    2. * precision * recall / (precision + recall)
 }
 
 /// # Returns
@@ -136,7 +293,7 @@ pub fn response_as_placeholders(response: ResponseItem) -> Vec<String> {
 }
 
 #[non_exhaustive]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum BlockAnswered {
     Order {
         items: Vec<String>,
@@ -155,7 +312,7 @@ pub enum BlockAnswered {
     },
     Paragraph(Vec<ParagraphItemAnswered>),
 }
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum ParagraphItemAnswered {
     Text(String),
     Answer {
@@ -166,8 +323,16 @@ pub enum ParagraphItemAnswered {
 impl From<(Block, Vec<String>, Vec<String>)> for BlockAnswered {
     fn from((block, user_answer, correct_answer): (Block, Vec<String>, Vec<String>)) -> Self {
         match block {
-            Block::Order(_) => todo!(),
-            Block::AnyOf(_) => todo!(),
+            Block::Order(items) => Self::Order {
+                items,
+                user_answer: parse_positions(user_answer),
+                correct_answer: parse_positions(correct_answer),
+            },
+            Block::AnyOf(items) => Self::AnyOf {
+                items,
+                user_answer: parse_positions(user_answer),
+                correct_answer: parse_positions(correct_answer),
+            },
             Block::OneOf(items) => {
                 let [user_answer] = user_answer.try_into().unwrap();
                 let [correct_answer] = correct_answer.try_into().unwrap();
@@ -217,8 +382,36 @@ impl From<(Block, Vec<String>, Vec<String>)> for BlockAnswered {
     }
 }
 
+/// Malformed answer becomes empty, just as [`grade_block`] scores it `0.0`.
+fn parse_positions(answer: Vec<String>) -> Vec<usize> {
+    try_parse_positions(&answer).unwrap_or_default()
+}
+fn try_parse_positions(answer: &[String]) -> Result<Vec<usize>, ParseIntError> {
+    answer.iter().map(|x| x.parse()).collect()
+}
+
+/// Unparsable positions of `Order` and `AnyOf` answers are shown as empty answer.
+///
 /// # Panics
 /// Panics if the length of `user_answers` does not match the length of `correct_answers`.
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f::{Block, BlockAnswered, to_answered};
+///
+/// let blocks = vec![Block::Order(vec!["a".into(), "b".into()])];
+/// let user = vec![vec!["1".into(), "first".into()]];
+/// let correct = vec![vec!["0".into(), "1".into()]];
+/// assert_eq!(
+///     to_answered(blocks, user, correct),
+///     vec![BlockAnswered::Order {
+///         items: vec!["a".into(), "b".into()],
+///         user_answer: vec![],
+///         correct_answer: vec![0, 1],
+///     }]
+/// );
+/// ```
 #[must_use]
 pub fn to_answered(
     blocks: Blocks,
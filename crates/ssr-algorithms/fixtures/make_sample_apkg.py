@@ -0,0 +1,96 @@
+"""Generates `sample.apkg` used by `ssr_algorithms::fsrs::anki` doctests.
+
+Collection uses legacy schema 11 and contains:
+- basic note with 3 reviews,
+- cloze note with 2 clozes, first one reviewed twice, second one new,
+- basic note with reversed card, both new.
+"""
+
+import os
+import sqlite3
+import tempfile
+import zipfile
+
+DAY_MS = 24 * 60 * 60 * 1000
+START_MS = 1_700_000_000_000
+
+SCHEMA = """
+CREATE TABLE col (
+    id integer primary key, crt integer not null, mod integer not null,
+    scm integer not null, ver integer not null, dty integer not null,
+    usn integer not null, ls integer not null, conf text not null,
+    models text not null, decks text not null, dconf text not null, tags text not null
+);
+CREATE TABLE notes (
+    id integer primary key, guid text not null, mid integer not null,
+    mod integer not null, usn integer not null, tags text not null,
+    flds text not null, sfld integer not null, csum integer not null,
+    flags integer not null, data text not null
+);
+CREATE TABLE cards (
+    id integer primary key, nid integer not null, did integer not null,
+    ord integer not null, mod integer not null, usn integer not null,
+    type integer not null, queue integer not null, due integer not null,
+    ivl integer not null, factor integer not null, reps integer not null,
+    lapses integer not null, left integer not null, odue integer not null,
+    odid integer not null, flags integer not null, data text not null
+);
+CREATE TABLE revlog (
+    id integer primary key, cid integer not null, usn integer not null,
+    ease integer not null, ivl integer not null, lastIvl integer not null,
+    factor integer not null, time integer not null, type integer not null
+);
+"""
+
+NOTES = [
+    (1, "What is the capital of <b>France</b>?\x1fParis", "geography"),
+    (2, "{{c1::Rust}} 1.0 was released in {{c2::2015::year}}.<br>\x1f", "rust"),
+    (3, "hund\x1fdog", "german"),
+]
+# (card id, note id, ord)
+CARDS = [(11, 1, 0), (21, 2, 0), (22, 2, 1), (31, 3, 0), (32, 3, 1)]
+# (card id, day, ease, type)
+REVLOG = [
+    (11, 0, 3, 0),
+    (11, 1, 3, 1),
+    (11, 5, 1, 1),
+    (21, 0, 3, 0),
+    (21, 3, 4, 1),
+    # manual reschedule, must be skipped
+    (21, 4, 0, 4),
+]
+
+
+def main():
+    here = os.path.dirname(os.path.abspath(__file__))
+    with tempfile.TemporaryDirectory() as tmp:
+        db_path = os.path.join(tmp, "collection.anki2")
+        db = sqlite3.connect(db_path)
+        db.executescript(SCHEMA)
+        db.execute(
+            "INSERT INTO col VALUES (1, 0, 0, 0, 11, 0, 0, 0, '{}', '{}', '{}', '{}', '{}')"
+        )
+        for nid, flds, tags in NOTES:
+            db.execute(
+                "INSERT INTO notes VALUES (?, ?, 0, 0, 0, ?, ?, 0, 0, 0, '')",
+                (nid, f"guid{nid}", f" {tags} ", flds),
+            )
+        for cid, nid, ord in CARDS:
+            db.execute(
+                "INSERT INTO cards VALUES (?, ?, 1, ?, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+                (cid, nid, ord),
+            )
+        for i, (cid, day, ease, kind) in enumerate(REVLOG):
+            db.execute(
+                "INSERT INTO revlog VALUES (?, ?, 0, ?, 0, 0, 0, 5000, ?)",
+                (START_MS + day * DAY_MS + i, cid, ease, kind),
+            )
+        db.commit()
+        db.close()
+        with zipfile.ZipFile(os.path.join(here, "sample.apkg"), "w", zipfile.ZIP_DEFLATED) as apkg:
+            apkg.write(db_path, "collection.anki2")
+            apkg.writestr("media", "{}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,230 @@
+//! Task of algorithm, that is chosen at runtime.
+
+use std::time::SystemTime;
+
+use s_text_input_f::BlocksWithAnswer;
+use serde::{Deserialize, Serialize};
+use ssr_core::{
+    review_log::Review,
+    task::{PastReview, SharedStateExt, Task as _},
+};
+
+use crate::{fsrs, leitner_system, super_memory_2};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    #[default]
+    Fsrs,
+    SuperMemory2,
+    Leitner,
+}
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Fsrs => "FSRS",
+            Self::SuperMemory2 => "SM-2",
+            Self::Leitner => "Leitner system",
+        })
+    }
+}
+
+/// Algorithm of deck and state of algorithms, that have it.
+/// Stored FSRS weights without algorithm are read as FSRS state.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct State {
+    #[serde(default)]
+    algorithm: Algorithm,
+    #[serde(flatten)]
+    weights: fsrs::weights::Weights,
+}
+impl State {
+    #[must_use]
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+    /// Only new tasks use `algorithm`, existing ones are moved by [`Task::migrate`].
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+    }
+    #[must_use]
+    pub fn weights(&self) -> &fsrs::weights::Weights {
+        &self.weights
+    }
+    pub fn set_weights(&mut self, weights: fsrs::weights::Weights) {
+        self.weights = weights;
+    }
+}
+impl ssr_core::task::SharedState<'_> for State {}
+
+/// Stored FSRS tasks are read as [`Task::Fsrs`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Task {
+    Fsrs(fsrs::Task),
+    SuperMemory2(super_memory_2::WriteAnswer),
+    Leitner(leitner_system::WriteAnswer),
+    /// Task, that was never completed. It gets algorithm of deck on the first completion.
+    New {
+        blocks: s_text_input_f::Blocks,
+        answer: s_text_input_f::Response,
+    },
+}
+
+impl Task {
+    #[must_use]
+    pub fn algorithm(&self) -> Option<Algorithm> {
+        match self {
+            Task::Fsrs(_) => Some(Algorithm::Fsrs),
+            Task::SuperMemory2(_) => Some(Algorithm::SuperMemory2),
+            Task::Leitner(_) => Some(Algorithm::Leitner),
+            Task::New { .. } => None,
+        }
+    }
+    #[must_use]
+    pub fn fsrs(&self) -> Option<&fsrs::Task> {
+        match self {
+            Task::Fsrs(task) => Some(task),
+            _ => None,
+        }
+    }
+    fn started(algorithm: Algorithm, input: BlocksWithAnswer) -> Self {
+        use ssr_core::task::Task as T;
+        match algorithm {
+            Algorithm::Fsrs => Task::Fsrs(<fsrs::Task as T>::new(input)),
+            Algorithm::SuperMemory2 => {
+                Task::SuperMemory2(<super_memory_2::WriteAnswer as T>::new(input))
+            }
+            Algorithm::Leitner => Task::Leitner(<leitner_system::WriteAnswer as T>::new(input)),
+        }
+    }
+    /// Same task in algorithm of `state`, its history is replayed, so scheduling continues.
+    /// Task, whose reviews are unknown, becomes new.
+    #[must_use]
+    pub fn migrate(&self, state: &mut State) -> Self {
+        if self.algorithm().is_none_or(|x| x == state.algorithm) {
+            return self.clone();
+        }
+        let history = self.history();
+        let mut task = Self::new(self.get_blocks());
+        task.replay(state, &history);
+        task
+    }
+}
+
+impl ssr_core::task::Task<'_> for Task {
+    type SharedState = State;
+
+    fn new(input: BlocksWithAnswer) -> Self {
+        Task::New {
+            blocks: input.blocks,
+            answer: input.answer,
+        }
+    }
+
+    fn get_blocks(&self) -> BlocksWithAnswer {
+        match self {
+            Task::Fsrs(task) => task.get_blocks(),
+            Task::SuperMemory2(task) => task.get_blocks(),
+            Task::Leitner(task) => task.get_blocks(),
+            Task::New { blocks, answer } => BlocksWithAnswer {
+                blocks: blocks.clone(),
+                answer: answer.clone(),
+            },
+        }
+    }
+
+    fn update_blocks(&mut self, input: BlocksWithAnswer) {
+        match self {
+            Task::Fsrs(task) => task.update_blocks(input),
+            Task::SuperMemory2(task) => task.update_blocks(input),
+            Task::Leitner(task) => task.update_blocks(input),
+            Task::New { .. } => *self = Self::new(input),
+        }
+    }
+
+    fn next_repetition(&self, state: &State, desired_retention: f64) -> SystemTime {
+        match self {
+            Task::Fsrs(task) => task.next_repetition(&state.weights, desired_retention),
+            Task::SuperMemory2(task) => task.next_repetition(&(), desired_retention),
+            Task::Leitner(task) => task.next_repetition(&(), desired_retention),
+            Task::New { .. } => SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    fn is_new(&self) -> bool {
+        match self {
+            Task::Fsrs(task) => task.is_new(),
+            Task::SuperMemory2(task) => task.is_new(),
+            Task::Leitner(task) => task.is_new(),
+            Task::New { .. } => true,
+        }
+    }
+
+    fn retrievability(&self, state: &State, now: SystemTime) -> Option<f64> {
+        match self {
+            Task::Fsrs(task) => task.retrievability(&state.weights, now),
+            Task::SuperMemory2(task) => task.retrievability(&(), now),
+            Task::Leitner(task) => task.retrievability(&(), now),
+            Task::New { .. } => None,
+        }
+    }
+
+    fn complete(
+        &mut self,
+        state: &mut State,
+        desired_retention: f64,
+        interaction: &mut impl FnMut(
+            s_text_input_f::Blocks,
+        ) -> std::io::Result<s_text_input_f::Response>,
+    ) -> std::io::Result<Review> {
+        match self {
+            Task::Fsrs(task) => task.complete(&mut state.weights, desired_retention, interaction),
+            Task::SuperMemory2(task) => task.complete(&mut (), desired_retention, interaction),
+            Task::Leitner(task) => task.complete(&mut (), desired_retention, interaction),
+            Task::New { .. } => {
+                let mut task = Self::started(state.algorithm, self.get_blocks());
+                let review = task.complete(state, desired_retention, interaction)?;
+                *self = task;
+                Ok(review)
+            }
+        }
+    }
+
+    fn history(&self) -> Vec<PastReview> {
+        match self {
+            Task::Fsrs(task) => task.history(),
+            Task::SuperMemory2(task) => task.history(),
+            Task::Leitner(task) => task.history(),
+            Task::New { .. } => Vec::new(),
+        }
+    }
+
+    /// New task gets algorithm of `state`, if there are reviews to replay.
+    fn replay(&mut self, state: &mut State, reviews: &[PastReview]) {
+        match self {
+            Task::Fsrs(task) => task.replay(&mut state.weights, reviews),
+            Task::SuperMemory2(task) => task.replay(&mut (), reviews),
+            Task::Leitner(task) => task.replay(&mut (), reviews),
+            Task::New { .. } if reviews.is_empty() => (),
+            Task::New { .. } => {
+                let mut task = Self::started(state.algorithm, self.get_blocks());
+                task.replay(state, reviews);
+                *self = task;
+            }
+        }
+    }
+}
+
+/// Only FSRS tasks are used, state of other algorithms is kept.
+impl SharedStateExt<'_, Task> for State {
+    fn optimize<'b>(
+        &mut self,
+        tasks: impl IntoIterator<Item = &'b Task>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        Task: 'b,
+    {
+        self.weights
+            .optimize(tasks.into_iter().filter_map(Task::fsrs))
+    }
+}
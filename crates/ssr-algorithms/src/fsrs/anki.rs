@@ -0,0 +1,326 @@
+//! Import of Anki packages (`.apkg`, `.colpkg`) into FSRS tasks.
+//!
+//! Each Anki card becomes separate task. Cloze deletions become placeholders, other notes
+//! are asked front to back (or back to front for reversed cards). Review history is replayed,
+//! so scheduling continues from where Anki stopped.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Local};
+use rusqlite::Connection;
+use s_text_input_f::{Block, ParagraphItem};
+use thiserror::Error;
+
+use super::{
+    Task,
+    level::{Quality, RepetitionContext},
+    steps::Steps,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("package doesn't contain Anki collection")]
+    NoCollection,
+    #[error("can't read package: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("can't read collection: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+/// Collection files, newest format first.
+/// `collection.anki2` in new packages only contains note asking to update Anki.
+const COLLECTIONS: [(&str, bool); 3] = [
+    ("collection.anki21b", true),
+    ("collection.anki21", false),
+    ("collection.anki2", false),
+];
+
+/// Revlog entries of these types are actual reviews: learn, review, relearn and filtered.
+const REVIEW_TYPES: [u8; 4] = [0, 1, 2, 3];
+
+/// Reads all cards of Anki package.
+///
+/// Cards, that can't be asked (e.g. with empty answer), are skipped. Learning reviews are
+/// replayed with `steps` of the deck, that cards are imported into.
+/// # Errors
+/// If package can't be read or has unknown format.
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f::{Block, ParagraphItem};
+/// use ssr_algorithms::fsrs::{anki, level::Level, steps::Steps};
+/// use ssr_core::task::Task;
+///
+/// let path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/sample.apkg");
+/// let tasks = anki::import(path, &Steps::default()).unwrap();
+/// // basic card, 2 clozes and card with reversed one
+/// assert_eq!(tasks.len(), 5);
+///
+/// let basic = tasks[0].get_blocks();
+/// assert_eq!(basic.answer, [vec![], vec!["Paris".to_owned()]]);
+/// assert_eq!(tasks[0].level().lapses(), 1);
+///
+/// let cloze = tasks[2].get_blocks();
+/// let [Block::Paragraph(items)] = cloze.blocks.as_slice() else {
+///     panic!("cloze must be single paragraph");
+/// };
+/// assert!(matches!(
+///     items.as_slice(),
+///     [
+///         ParagraphItem::Text(before),
+///         ParagraphItem::Placeholder,
+///         ParagraphItem::Text(hint),
+///     ] if before == "Rust 1.0 was released in " && hint == " (year)."
+/// ));
+/// assert_eq!(cloze.answer, [vec!["2015".to_owned()]]);
+/// assert!(!matches!(tasks[1].level(), Level::NotStarted));
+/// assert!(matches!(tasks[2].level(), Level::NotStarted));
+///
+/// let reversed = tasks[4].get_blocks();
+/// assert_eq!(reversed.answer, [vec![], vec!["hund".to_owned()]]);
+/// ```
+pub fn import(path: impl AsRef<Path>, steps: &Steps) -> Result<Vec<Task>, Error> {
+    let collection = extract_collection(path.as_ref())?;
+    let connection = Connection::open(collection.path())?;
+
+    let mut reviews = HashMap::<i64, Vec<RepetitionContext>>::new();
+    let mut statement = connection.prepare("SELECT cid, id, ease, type FROM revlog ORDER BY id")?;
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, u8>(2)?,
+            row.get::<_, u8>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (card, timestamp, ease, kind) = row?;
+        let (Some(quality), Some(review_time)) =
+            (quality(ease), DateTime::from_timestamp_millis(timestamp))
+        else {
+            continue;
+        };
+        if !REVIEW_TYPES.contains(&kind) {
+            continue;
+        }
+        reviews.entry(card).or_default().push(RepetitionContext {
+            quality,
+            review_time: review_time.with_timezone(&Local),
+        });
+    }
+
+    let mut statement = connection.prepare(
+        "SELECT cards.id, cards.ord, notes.flds FROM cards
+         JOIN notes ON notes.id = cards.nid ORDER BY cards.nid, cards.ord",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, usize>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    let mut tasks = Vec::new();
+    for row in rows {
+        let (card, ord, fields) = row?;
+        let fields = fields.split('\x1f').map(html_to_text).collect::<Vec<_>>();
+        let Some((blocks, answer)) = card_blocks(&fields, ord) else {
+            continue;
+        };
+        let mut task = Task::new(blocks, answer, Vec::new());
+        for repetition in reviews.remove(&card).unwrap_or_default() {
+            task.level.add_repetition(repetition, steps);
+        }
+        tasks.push(task);
+    }
+    Ok(tasks)
+}
+
+fn quality(ease: u8) -> Option<Quality> {
+    match ease {
+        1 => Some(Quality::Again),
+        2 => Some(Quality::Hard),
+        3 => Some(Quality::Good),
+        4 => Some(Quality::Easy),
+        _ => None,
+    }
+}
+
+fn extract_collection(path: &Path) -> Result<tempfile::NamedTempFile, Error> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let (name, compressed) = COLLECTIONS
+        .into_iter()
+        .find(|(name, _)| archive.index_for_name(name).is_some())
+        .ok_or(Error::NoCollection)?;
+    let mut content = Vec::new();
+    archive.by_name(name)?.read_to_end(&mut content)?;
+    if compressed {
+        content = zstd::decode_all(content.as_slice())?;
+    }
+    let mut collection = tempfile::NamedTempFile::new()?;
+    collection.write_all(&content)?;
+    collection.flush()?;
+    Ok(collection)
+}
+
+/// Part of cloze note text.
+enum Segment<'a> {
+    Text(&'a str),
+    Cloze {
+        number: usize,
+        answer: &'a str,
+        hint: Option<&'a str>,
+    },
+}
+
+/// Splits text with `{{c<number>::answer::hint}}` deletions.
+fn cloze_segments(mut text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    while let Some(start) = text.find("{{c") {
+        let cloze = text[start + 3..]
+            .split_once("::")
+            .and_then(|(number, rest)| {
+                let number = number.parse().ok()?;
+                let (content, rest) = rest.split_once("}}")?;
+                let (answer, hint) = match content.split_once("::") {
+                    Some((answer, hint)) => (answer, Some(hint)),
+                    None => (content, None),
+                };
+                Some((number, answer, hint, rest))
+            });
+        let Some((number, answer, hint, rest)) = cloze else {
+            segments.push(Segment::Text(&text[..start + 3]));
+            text = &text[start + 3..];
+            continue;
+        };
+        segments.push(Segment::Text(&text[..start]));
+        segments.push(Segment::Cloze {
+            number,
+            answer,
+            hint,
+        });
+        text = rest;
+    }
+    segments.push(Segment::Text(text));
+    segments
+}
+
+/// Blocks and correct answer of card number `ord` of note with `fields`.
+fn card_blocks(
+    fields: &[String],
+    ord: usize,
+) -> Option<(s_text_input_f::Blocks, s_text_input_f::Response)> {
+    let text = fields.first()?;
+    let segments = cloze_segments(text);
+    if segments.iter().any(|x| matches!(x, Segment::Cloze { .. })) {
+        let mut items = Vec::new();
+        let mut answers = Vec::new();
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => items.push(ParagraphItem::Text(text.to_owned())),
+                Segment::Cloze { number, answer, .. } if number != ord + 1 => {
+                    items.push(ParagraphItem::Text(answer.to_owned()));
+                }
+                Segment::Cloze { answer, hint, .. } => {
+                    items.push(ParagraphItem::Placeholder);
+                    answers.push(answer.trim().to_owned());
+                    if let Some(hint) = hint {
+                        items.push(ParagraphItem::Text(format!(" ({hint})")));
+                    }
+                }
+            }
+        }
+        let items = merge_text(items);
+        if answers.is_empty() || answers.iter().any(String::is_empty) {
+            return None;
+        }
+        return Some((vec![Block::Paragraph(items)], vec![answers]));
+    }
+
+    let (front, back) = match ord {
+        0 => (fields.first()?, fields.get(1)?),
+        1 => (fields.get(1)?, fields.first()?),
+        _ => return None,
+    };
+    if front.is_empty() || back.is_empty() {
+        return None;
+    }
+    Some((
+        vec![
+            Block::Paragraph(vec![ParagraphItem::Text(front.clone())]),
+            Block::Paragraph(vec![ParagraphItem::Placeholder]),
+        ],
+        vec![vec![], vec![back.clone()]],
+    ))
+}
+
+/// Joins adjacent text items and removes empty ones.
+fn merge_text(items: Vec<ParagraphItem>) -> Vec<ParagraphItem> {
+    let mut merged: Vec<ParagraphItem> = Vec::new();
+    for item in items {
+        match (merged.last_mut(), item) {
+            (_, ParagraphItem::Text(text)) if text.is_empty() => (),
+            (Some(ParagraphItem::Text(last)), ParagraphItem::Text(text)) => last.push_str(&text),
+            (_, item) => merged.push(item),
+        }
+    }
+    merged
+}
+
+/// Converts field HTML to plain text: line breaks are kept, other tags, sounds and images are
+/// removed.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find(['<', '[']) {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = if rest.starts_with('<') {
+            rest.find('>')
+        } else if rest.starts_with("[sound:") {
+            rest.find(']')
+        } else {
+            text.push('[');
+            rest = &rest[1..];
+            continue;
+        };
+        let Some(end) = end else {
+            break;
+        };
+        let tag = rest[1..end].trim().to_ascii_lowercase();
+        if ["br", "br/", "br /", "div", "/div", "p", "/p", "li"]
+            .iter()
+            .any(|x| tag == *x || tag.starts_with(&format!("{x} ")))
+        {
+            text.push('\n');
+        }
+        rest = &rest[end + 1..];
+    }
+    text.push_str(rest);
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    let lines = text.lines().map(str::trim_end).collect::<Vec<_>>();
+    let mut result = String::new();
+    for line in lines {
+        if line.is_empty() && (result.is_empty() || result.ends_with("\n\n")) {
+            continue;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result.trim().to_owned()
+}
@@ -1,8 +1,11 @@
 use std::time::{Duration, SystemTime};
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Days, Local};
 use fsrs::{FSRS, FSRSItem, FSRSReview};
 use serde::{Deserialize, Serialize};
+use ssr_core::task::{Grade, PastReview};
+
+use super::steps::Steps;
 
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[repr(u32)]
@@ -12,6 +15,38 @@ pub enum Quality {
     Good = 3,
     Easy = 4,
 }
+impl From<Grade> for Quality {
+    fn from(grade: Grade) -> Self {
+        match grade {
+            Grade::Again => Self::Again,
+            Grade::Hard => Self::Hard,
+            Grade::Good => Self::Good,
+            Grade::Easy => Self::Easy,
+        }
+    }
+}
+impl Quality {
+    fn from_rating(rating: u32) -> Option<Self> {
+        match rating {
+            1 => Some(Self::Again),
+            2 => Some(Self::Hard),
+            3 => Some(Self::Good),
+            4 => Some(Self::Easy),
+            _ => None,
+        }
+    }
+}
+impl From<Quality> for Grade {
+    fn from(quality: Quality) -> Self {
+        match quality {
+            Quality::Again => Self::Again,
+            Quality::Hard => Self::Hard,
+            Quality::Good => Self::Good,
+            Quality::Easy => Self::Easy,
+        }
+    }
+}
+
 pub struct RepetitionContext {
     pub quality: Quality,
     pub review_time: chrono::DateTime<chrono::Local>,
@@ -24,35 +59,81 @@ pub enum Level {
     #[default]
     NotStarted,
 }
+
+/// Time until the next repetition for each quality, if task is completed now.
+#[derive(Debug, Clone, Copy)]
+pub struct NextIntervals {
+    pub again: Duration,
+    pub hard: Duration,
+    pub good: Duration,
+    pub easy: Duration,
+}
+
 impl Level {
-    pub fn next_states(
+    pub fn next_intervals(
         &self,
         fsrs: &FSRS,
-        retrievability_goal: f32,
+        steps: &Steps,
+        retrievability_goal: f64,
         now: DateTime<Local>,
-    ) -> fsrs::NextStates {
-        match self {
-            Level::Started(level) => level.next_states(fsrs, retrievability_goal, now),
-            Level::NotStarted => fsrs.next_states(None, retrievability_goal, 0).unwrap(),
+    ) -> NextIntervals {
+        let interval = |quality| {
+            let mut level = self.clone();
+            level.add_repetition(
+                RepetitionContext {
+                    quality,
+                    review_time: now,
+                },
+                steps,
+            );
+            level
+                .next_repetition(fsrs, steps, retrievability_goal)
+                .duration_since(now.into())
+                .unwrap_or_default()
+        };
+        NextIntervals {
+            again: interval(Quality::Again),
+            hard: interval(Quality::Hard),
+            good: interval(Quality::Good),
+            easy: interval(Quality::Easy),
         }
     }
 
-    pub fn next_repetition(&self, fsrs: &FSRS, retrievability_goal: f64) -> SystemTime {
+    /// Probability to recall at `now`, `None` if not started.
+    pub fn retrievability(&self, fsrs: &FSRS, now: DateTime<Local>) -> Option<f32> {
         match self {
-            Level::Started(level) => level.next_repetition(fsrs, retrievability_goal),
+            Level::Started(level) => {
+                let days_elapsed = sleeps_between(&level.last_review, &now).max(0);
+                Some(fsrs.current_retrievability(
+                    level.memory_state(fsrs),
+                    days_elapsed.try_into().unwrap(),
+                ))
+            }
+            Level::NotStarted => None,
+        }
+    }
+    pub fn next_repetition(
+        &self,
+        fsrs: &FSRS,
+        steps: &Steps,
+        retrievability_goal: f64,
+    ) -> SystemTime {
+        match self {
+            Level::Started(level) => level.next_repetition(fsrs, steps, retrievability_goal),
             Level::NotStarted => SystemTime::UNIX_EPOCH,
         }
     }
 
-    pub fn add_repetition(&mut self, repetition: RepetitionContext) {
+    pub fn add_repetition(&mut self, repetition: RepetitionContext, steps: &Steps) {
         match self {
             Level::Started(level) => {
-                level.add_repetition(repetition);
+                level.add_repetition(repetition, steps);
             }
             Level::NotStarted => {
                 *self = Level::Started(StartedLevel::new(
                     repetition.quality,
                     repetition.review_time,
+                    steps,
                 ));
             }
         }
@@ -70,6 +151,46 @@ impl Level {
             Level::NotStarted => None,
         }
     }
+    /// Only days of reviews are known, so earlier reviews get time of the last one.
+    pub fn past_reviews(&self) -> Vec<PastReview> {
+        let Level::Started(level) = self else {
+            return Vec::new();
+        };
+        let mut day = level.last_review;
+        let mut reviews = Vec::with_capacity(level.history.reviews.len());
+        for review in level.history.reviews.iter().rev() {
+            if let Some(quality) = Quality::from_rating(review.rating) {
+                reviews.push(PastReview {
+                    timestamp: day.into(),
+                    grade: quality.into(),
+                });
+            }
+            day = day
+                .checked_sub_days(Days::new(review.delta_t.into()))
+                .unwrap_or(day);
+        }
+        reviews.reverse();
+        reviews
+    }
+    pub fn memory_state(&self, fsrs: &FSRS) -> Option<fsrs::MemoryState> {
+        match self {
+            Level::Started(level) => Some(level.memory_state(fsrs)),
+            Level::NotStarted => None,
+        }
+    }
+    /// Number of times task was forgotten after the first review.
+    pub fn lapses(&self) -> usize {
+        match self {
+            Level::Started(level) => level
+                .history
+                .reviews
+                .iter()
+                .skip(1)
+                .filter(|review| review.rating == Quality::Again as u32)
+                .count(),
+            Level::NotStarted => 0,
+        }
+    }
     pub fn failed(&self) -> bool {
         match self {
             Self::NotStarted => false,
@@ -78,14 +199,76 @@ impl Level {
     }
 }
 
+/// Learning steps are completed before task graduates into long-term scheduling.
+/// Tasks saved before steps were introduced are in review phase.
+#[derive(Default, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Learning {
+        step: usize,
+    },
+    #[default]
+    Review,
+    Relearning {
+        step: usize,
+    },
+}
+impl Phase {
+    fn first(quality: Quality, steps: &Steps) -> Self {
+        Self::advance(quality, 0, &steps.learning, |step| Phase::Learning { step })
+    }
+    fn next(self, quality: Quality, steps: &Steps) -> Self {
+        match self {
+            Phase::Learning { step } => Self::advance(quality, step, &steps.learning, |step| {
+                Phase::Learning { step }
+            }),
+            Phase::Review if quality == Quality::Again && !steps.relearning.is_empty() => {
+                Phase::Relearning { step: 0 }
+            }
+            Phase::Review => Phase::Review,
+            Phase::Relearning { step } => Self::advance(quality, step, &steps.relearning, |step| {
+                Phase::Relearning { step }
+            }),
+        }
+    }
+    fn advance(
+        quality: Quality,
+        step: usize,
+        steps: &[Duration],
+        phase: impl FnOnce(usize) -> Self,
+    ) -> Self {
+        let step = match quality {
+            Quality::Again => 0,
+            Quality::Hard => step,
+            Quality::Good => step + 1,
+            Quality::Easy => return Phase::Review,
+        };
+        if step < steps.len() {
+            phase(step)
+        } else {
+            Phase::Review
+        }
+    }
+    /// `None` if task is scheduled by FSRS, also when steps were shortened.
+    fn delay(self, steps: &Steps) -> Option<Duration> {
+        match self {
+            Phase::Learning { step } => steps.learning.get(step).copied(),
+            Phase::Review => None,
+            Phase::Relearning { step } => steps.relearning.get(step).copied(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct StartedLevel {
     last_quality: Quality,
     last_review: chrono::DateTime<chrono::Local>,
+    /// Reviews on the same day have zero `delta_t`, FSRS uses short-term stability for them.
     history: FSRSItem,
+    #[serde(default)]
+    phase: Phase,
 }
 impl StartedLevel {
-    fn new(quality: Quality, review_time: chrono::DateTime<chrono::Local>) -> Self {
+    fn new(quality: Quality, review_time: chrono::DateTime<chrono::Local>, steps: &Steps) -> Self {
         Self {
             last_quality: quality,
             last_review: review_time,
@@ -95,12 +278,13 @@ impl StartedLevel {
                     delta_t: 0,
                 }],
             },
+            phase: Phase::first(quality, steps),
         }
     }
     fn memory_state(&self, fsrs: &FSRS) -> fsrs::MemoryState {
         fsrs.memory_state(self.history.clone(), None).unwrap()
     }
-    fn add_repetition(&mut self, repetition: RepetitionContext) {
+    fn add_repetition(&mut self, repetition: RepetitionContext, steps: &Steps) {
         self.history.reviews.push(FSRSReview {
             rating: repetition.quality as u32,
             delta_t: sleeps_between(&self.last_review, &repetition.review_time)
@@ -109,8 +293,12 @@ impl StartedLevel {
         });
         self.last_quality = repetition.quality;
         self.last_review = repetition.review_time;
+        self.phase = self.phase.next(repetition.quality, steps);
     }
-    fn next_repetition(&self, fsrs: &FSRS, retrievability_goal: f64) -> SystemTime {
+    fn next_repetition(&self, fsrs: &FSRS, steps: &Steps, retrievability_goal: f64) -> SystemTime {
+        if let Some(delay) = self.phase.delay(steps) {
+            return SystemTime::from(self.last_review) + delay;
+        }
         let interval_in_days = fsrs.next_interval(
             Some(self.memory_state(fsrs).stability),
             retrievability_goal as f32,
@@ -121,19 +309,6 @@ impl StartedLevel {
 
         SystemTime::from(self.last_review) + interval
     }
-    fn next_states(
-        &self,
-        fsrs: &FSRS,
-        retrievability_goal: f32,
-        now: DateTime<Local>,
-    ) -> fsrs::NextStates {
-        fsrs.next_states(
-            Some(self.memory_state(fsrs)),
-            retrievability_goal,
-            sleeps_between(&self.last_review, &now).try_into().unwrap(),
-        )
-        .unwrap()
-    }
 }
 
 fn sleeps_between(first: &impl chrono::Datelike, second: &impl chrono::Datelike) -> i32 {
@@ -1,14 +1,20 @@
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 use s_text_input_f as stif;
 use s_text_input_f::{BlocksWithAnswer, ParagraphItem};
 use serde::{Deserialize, Serialize};
+use ssr_core::{review_log::Review, task::PastReview};
 
+#[cfg(feature = "anki")]
+pub mod anki;
 pub mod level;
-use level::{Level, Quality, RepetitionContext};
+use level::{Level, NextIntervals, Quality, RepetitionContext};
+use steps::format_interval;
 use weights::Weights;
 
+pub mod simulator;
 pub mod stateless;
+pub mod steps;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Task {
@@ -21,12 +27,28 @@ pub struct Task {
 
 pub mod weights;
 
+/// Wrong answers graded at least this high can be rated `Hard` instead of `Again`.
+const PARTIAL_CREDIT_THRESHOLD: f64 = 0.8;
+
 impl ssr_core::task::Task<'_> for Task {
     type SharedState = Weights;
 
     fn next_repetition(&self, shared_state: &Weights, retrievability_goal: f64) -> SystemTime {
+        self.level.next_repetition(
+            &shared_state.fsrs(),
+            shared_state.steps(),
+            retrievability_goal,
+        )
+    }
+
+    fn is_new(&self) -> bool {
+        matches!(self.level, Level::NotStarted)
+    }
+
+    fn retrievability(&self, shared_state: &Weights, now: SystemTime) -> Option<f64> {
         self.level
-            .next_repetition(&shared_state.fsrs(), retrievability_goal)
+            .retrievability(&shared_state.fsrs(), now.into())
+            .map(f64::from)
     }
 
     fn complete(
@@ -36,16 +58,40 @@ impl ssr_core::task::Task<'_> for Task {
         interaction: &mut impl FnMut(
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> std::io::Result<()> {
+    ) -> std::io::Result<Review> {
         let review_time = chrono::Local::now();
+        let state_before = Review::snapshot(&self.level);
+        let was_new = self.is_new();
+        let started = Instant::now();
         let user_answer = interaction(self.input_blocks.clone())?;
-        let quality =
-            self.complete_inner(user_answer, shared_state, desired_retention, interaction)?;
-        self.level.add_repetition(RepetitionContext {
-            quality,
-            review_time,
-        });
-        Ok(())
+        let elapsed = started.elapsed();
+        let quality = self.complete_inner(
+            user_answer.clone(),
+            shared_state,
+            desired_retention,
+            interaction,
+        )?;
+        self.level.add_repetition(
+            RepetitionContext {
+                quality,
+                review_time,
+            },
+            shared_state.steps(),
+        );
+        let timestamp = SystemTime::from(review_time);
+        Ok(Review {
+            timestamp,
+            grade: quality.into(),
+            recalled: (!was_new).then_some(quality != Quality::Again),
+            elapsed,
+            response: user_answer,
+            interval: self
+                .next_repetition(shared_state, desired_retention)
+                .duration_since(timestamp)
+                .unwrap_or_default(),
+            state_before,
+            state_after: Review::snapshot(&self.level),
+        })
     }
 
     fn new(input: s_text_input_f::BlocksWithAnswer) -> Self {
@@ -63,6 +109,29 @@ impl ssr_core::task::Task<'_> for Task {
             answer: self.correct_answer.clone(),
         }
     }
+
+    /// Other answers are dropped, because they were accepted for previous question.
+    fn update_blocks(&mut self, input: s_text_input_f::BlocksWithAnswer) {
+        self.input_blocks = input.blocks;
+        self.correct_answer = input.answer;
+        self.other_answers.clear();
+    }
+
+    fn history(&self) -> Vec<PastReview> {
+        self.level.past_reviews()
+    }
+
+    fn replay(&mut self, shared_state: &mut Weights, reviews: &[PastReview]) {
+        for review in reviews {
+            self.level.add_repetition(
+                RepetitionContext {
+                    quality: review.grade.into(),
+                    review_time: review.timestamp.into(),
+                },
+                shared_state.steps(),
+            );
+        }
+    }
 }
 
 pub enum Correctness {
@@ -94,6 +163,10 @@ impl Task {
             other_answers,
         }
     }
+    #[must_use]
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
     fn gen_feedback_form(
         &mut self,
         user_answer: Vec<Vec<String>>,
@@ -143,13 +216,28 @@ impl Task {
         retrievability_goal: f64,
         interaction: &mut impl FnMut(s_text_input_f::Blocks) -> std::io::Result<Vec<Vec<String>>>,
     ) -> std::io::Result<Quality> {
-        let next_states = self.next_states(shared_state, retrievability_goal);
+        let next_intervals = self.next_intervals(shared_state, retrievability_goal);
         Ok(if self.correctness(&user_answer).is_correct() {
-            self.feedback_correct(user_answer, &next_states, interaction)?
+            self.feedback_correct(user_answer, &next_intervals, interaction)?
         } else {
-            self.feedback_wrong(user_answer, &next_states, interaction)?
+            let score = self.score(&user_answer);
+            self.feedback_wrong(user_answer, score, &next_intervals, interaction)?
         })
     }
+    /// Mean block grade of user answer against the closest of correct answers.
+    fn score(&self, user_answer: &s_text_input_f::Response) -> f64 {
+        std::iter::once(&self.correct_answer)
+            .chain(&self.other_answers)
+            .filter(|correct| correct.len() == user_answer.len())
+            .map(|correct| {
+                let grades =
+                    stif::grade_response(&self.input_blocks, user_answer, correct, true, false);
+                #[allow(clippy::cast_precision_loss)]
+                let count = grades.len().max(1) as f64;
+                grades.iter().sum::<f64>() / count
+            })
+            .fold(0.0, f64::max)
+    }
     fn correctness(&mut self, user_answer: &Vec<Vec<String>>) -> Correctness {
         if stif::eq_response(&self.correct_answer, user_answer, true, false) {
             return Correctness::DefaultCorrect;
@@ -162,10 +250,11 @@ impl Task {
         Correctness::Wrong
     }
 
-    fn next_states(&self, weights: &Weights, retrievability_goal: f64) -> fsrs::NextStates {
-        self.level.next_states(
+    fn next_intervals(&self, weights: &Weights, retrievability_goal: f64) -> NextIntervals {
+        self.level.next_intervals(
             &weights.fsrs(),
-            retrievability_goal as f32,
+            weights.steps(),
+            retrievability_goal,
             chrono::Local::now(),
         )
     }
@@ -173,14 +262,14 @@ impl Task {
     fn feedback_correct(
         &mut self,
         user_answer: Vec<Vec<String>>,
-        next_states: &fsrs::NextStates,
+        next_intervals: &NextIntervals,
         interaction: &mut impl FnMut(s_text_input_f::Blocks) -> std::io::Result<Vec<Vec<String>>>,
     ) -> std::io::Result<Quality> {
         let qualities = vec![Quality::Hard, Quality::Good, Quality::Easy];
         let qualities_strings = vec![
-            format!("Hard {}d", next_states.hard.interval),
-            format!("Good {}d", next_states.good.interval),
-            format!("Easy {}d", next_states.easy.interval),
+            format!("Hard {}", format_interval(next_intervals.hard)),
+            format!("Good {}", format_interval(next_intervals.good)),
+            format!("Easy {}", format_interval(next_intervals.easy)),
         ];
         let directive = "All answers correct! Choose difficulty:".to_string();
         self.get_feedback(
@@ -195,29 +284,40 @@ impl Task {
     fn feedback_wrong(
         &mut self,
         user_answer: Vec<Vec<String>>,
-        next_states: &fsrs::NextStates,
+        score: f64,
+        next_intervals: &NextIntervals,
         interaction: &mut impl FnMut(s_text_input_f::Blocks) -> std::io::Result<Vec<Vec<String>>>,
     ) -> std::io::Result<Quality> {
         #[derive(Clone, Copy)]
         enum Feedback {
             Wrong,
+            AlmostCorrect,
             ActuallyCorrect,
         }
+        let mut qualities_strings = vec![format!("OK {}", format_interval(next_intervals.again))];
+        let mut qualities = vec![Feedback::Wrong];
+        if score >= PARTIAL_CREDIT_THRESHOLD {
+            qualities_strings.push(format!(
+                "Almost correct: Hard {}",
+                format_interval(next_intervals.hard)
+            ));
+            qualities.push(Feedback::AlmostCorrect);
+        }
+        qualities_strings.push("It is actually correct".into());
+        qualities.push(Feedback::ActuallyCorrect);
         let result = self.get_feedback(
             user_answer.clone(),
-            "Your answer is wrong.".into(),
-            vec![
-                format!("OK {}h", next_states.again.interval * 24.),
-                "It is actually correct".into(),
-            ],
+            format!("Your answer is wrong ({:.0}% correct).", score * 100.),
+            qualities_strings,
             interaction,
-            &[Feedback::Wrong, Feedback::ActuallyCorrect],
+            &qualities,
         )?;
         match result {
             Feedback::Wrong => Ok(Quality::Again),
+            Feedback::AlmostCorrect => Ok(Quality::Hard),
             Feedback::ActuallyCorrect => {
                 self.other_answers.push(user_answer.clone());
-                self.feedback_correct(user_answer, next_states, interaction)
+                self.feedback_correct(user_answer, next_intervals, interaction)
             }
         }
     }
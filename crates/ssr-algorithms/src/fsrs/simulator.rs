@@ -0,0 +1,153 @@
+use fsrs::{FSRS, MemoryState};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use super::level::Quality;
+
+/// Seconds spent on the first review of task.
+const NEW_COST: f64 = 20.;
+/// Seconds spent on review of recalled task.
+const RECALLED_COST: f64 = 8.;
+/// Seconds spent on review of forgotten task, relearning included.
+const FORGOTTEN_COST: f64 = 25.;
+/// Larger decks are simulated by their part, see [`SimulationConfig::for_deck`].
+const MAX_TASKS: usize = 200;
+/// Used when deck has no daily limit of new tasks.
+const DEFAULT_NEW_PER_DAY: usize = 20;
+const DAYS: usize = 365;
+
+/// Desired retentions in percents, that [`retention_curve`] simulates.
+pub const RETENTIONS: std::ops::RangeInclusive<u32> = 70..=97;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationConfig {
+    /// Tasks, that are learned from scratch.
+    pub tasks: usize,
+    pub new_per_day: usize,
+    pub days: usize,
+    pub seed: u64,
+}
+impl SimulationConfig {
+    /// At most [`MAX_TASKS`] tasks are simulated and new tasks per day are scaled down with them,
+    /// so time per memorized task stays about the same.
+    #[must_use]
+    pub fn for_deck(tasks: usize, new_per_day: Option<usize>) -> Self {
+        let new_per_day = new_per_day.unwrap_or(DEFAULT_NEW_PER_DAY);
+        let simulated = tasks.min(MAX_TASKS);
+        let new_per_day = if simulated < tasks {
+            (new_per_day * simulated).div_ceil(tasks)
+        } else {
+            new_per_day
+        };
+        Self {
+            tasks: simulated,
+            new_per_day: new_per_day.max(1),
+            days: DAYS,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub desired_retention: f64,
+    /// Seconds spent on reviews each day.
+    pub daily_cost: Vec<f64>,
+    /// Expected number of tasks, that are recalled at the end.
+    pub memorized: f64,
+}
+impl Simulation {
+    #[must_use]
+    pub fn total_cost(&self) -> f64 {
+        self.daily_cost.iter().sum()
+    }
+    /// Seconds of reviews per task recalled at the end, lower is better.
+    #[must_use]
+    pub fn cost_per_memorized(&self) -> f64 {
+        if self.memorized > 0. {
+            self.total_cost() / self.memorized
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+struct SimulatedTask {
+    memory: MemoryState,
+    last_review: usize,
+    due: usize,
+}
+
+/// Simulates learning of tasks with FSRS: each review is recalled with probability
+/// predicted by `fsrs`. Same seed gives the same result.
+#[must_use]
+pub fn simulate(fsrs: &FSRS, config: &SimulationConfig, desired_retention: f64) -> Simulation {
+    let retention = desired_retention as f32;
+    let interval = |memory: MemoryState, quality: Quality| {
+        let days = fsrs.next_interval(Some(memory.stability), retention, quality as u32);
+        (days.round() as usize).max(1)
+    };
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let first = fsrs.next_states(None, retention, 0).unwrap().good.memory;
+    let mut tasks = Vec::<SimulatedTask>::with_capacity(config.tasks);
+    let mut daily_cost = vec![0.; config.days];
+
+    for (day, cost) in daily_cost.iter_mut().enumerate() {
+        for task in tasks.iter_mut().filter(|task| task.due <= day) {
+            let elapsed = u32::try_from(day - task.last_review).unwrap_or(u32::MAX);
+            let recalled = rng.random::<f32>() < fsrs.current_retrievability(task.memory, elapsed);
+            let states = fsrs
+                .next_states(Some(task.memory), retention, elapsed)
+                .unwrap();
+            let (memory, quality) = if recalled {
+                *cost += RECALLED_COST;
+                (states.good.memory, Quality::Good)
+            } else {
+                *cost += FORGOTTEN_COST;
+                (states.again.memory, Quality::Again)
+            };
+            task.memory = memory;
+            task.last_review = day;
+            task.due = day + interval(memory, quality);
+        }
+        let new = config.new_per_day.min(config.tasks - tasks.len());
+        for _ in 0..new {
+            *cost += NEW_COST;
+            tasks.push(SimulatedTask {
+                memory: first,
+                last_review: day,
+                due: day + interval(first, Quality::Good),
+            });
+        }
+    }
+
+    let memorized = tasks
+        .iter()
+        .map(|task| {
+            let elapsed = u32::try_from(config.days - task.last_review).unwrap_or(u32::MAX);
+            f64::from(fsrs.current_retrievability(task.memory, elapsed))
+        })
+        .sum();
+    Simulation {
+        desired_retention,
+        daily_cost,
+        memorized,
+    }
+}
+
+/// Simulations for each of [`RETENTIONS`], sorted by retention.
+#[must_use]
+pub fn retention_curve(fsrs: &FSRS, config: &SimulationConfig) -> Vec<Simulation> {
+    RETENTIONS
+        .map(|percent| simulate(fsrs, config, f64::from(percent) / 100.))
+        .collect()
+}
+
+/// Simulation with the least time per memorized task.
+/// `None`, if nothing is memorized in any of simulations, e.g. in empty deck.
+#[must_use]
+pub fn optimal_retention(curve: &[Simulation]) -> Option<&Simulation> {
+    curve
+        .iter()
+        .filter(|x| x.cost_per_memorized().is_finite())
+        .min_by(|a, b| a.cost_per_memorized().total_cmp(&b.cost_per_memorized()))
+}
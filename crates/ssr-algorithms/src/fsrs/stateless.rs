@@ -2,10 +2,11 @@ use std::time::SystemTime;
 
 use s_text_input_f as stif;
 use serde::{Deserialize, Serialize};
-use ssr_core::BlocksDatabaseId;
+use ssr_core::{BlocksDatabaseId, review_log::Review};
 
 use super::{
-    level::{Level, Quality, RepetitionContext},
+    level::{Level, NextIntervals, Quality, RepetitionContext},
+    steps::format_interval,
     weights::Weights,
 };
 
@@ -27,7 +28,7 @@ impl ssr_core::task::StatelessTask for StatelessTask {
 
     fn next_repetition(&self, weights: &Weights, retrievability_goal: f64) -> SystemTime {
         self.level
-            .next_repetition(&weights.fsrs(), retrievability_goal)
+            .next_repetition(&weights.fsrs(), weights.steps(), retrievability_goal)
     }
 
     fn complete(
@@ -36,21 +37,46 @@ impl ssr_core::task::StatelessTask for StatelessTask {
         shared_state: &mut Self::SharedState,
         desired_retention: f64,
         interaction: &mut impl FnMut(stif::Blocks) -> std::io::Result<stif::Response>,
-    ) -> std::io::Result<()> {
+    ) -> std::io::Result<Review> {
         let review_time = chrono::Local::now();
+        let state_before = Review::snapshot(&self.level);
+        let was_new = matches!(self.level, Level::NotStarted);
+        let started = std::time::Instant::now();
 
-        let next_states = self.next_states(shared_state, desired_retention as f32);
+        let next_intervals = self.next_intervals(shared_state, desired_retention);
         let quality = if is_correct {
-            self.feedback_correct(&next_states, interaction)?
+            self.feedback_correct(&next_intervals, interaction)?
         } else {
-            self.feedback_wrong(&next_states, interaction)?
+            self.feedback_wrong(&next_intervals, interaction)?
         };
 
-        self.level.add_repetition(RepetitionContext {
-            quality,
-            review_time,
-        });
-        Ok(())
+        self.level.add_repetition(
+            RepetitionContext {
+                quality,
+                review_time,
+            },
+            shared_state.steps(),
+        );
+        let timestamp = SystemTime::from(review_time);
+        // Answer is checked by caller, so only time spent on feedback is known.
+        Ok(Review {
+            timestamp,
+            grade: quality.into(),
+            recalled: (!was_new).then_some(quality != Quality::Again),
+            elapsed: started.elapsed(),
+            response: Vec::new(),
+            interval: self
+                .level
+                .next_repetition(
+                    &shared_state.fsrs(),
+                    shared_state.steps(),
+                    desired_retention,
+                )
+                .duration_since(timestamp)
+                .unwrap_or_default(),
+            state_before,
+            state_after: Review::snapshot(&self.level),
+        })
     }
 
     fn get_id(&self) -> BlocksDatabaseId {
@@ -61,16 +87,16 @@ impl ssr_core::task::StatelessTask for StatelessTask {
 impl StatelessTask {
     fn feedback_correct(
         &self,
-        next_states: &fsrs::NextStates,
+        next_intervals: &NextIntervals,
         interaction: &mut impl FnMut(stif::Blocks) -> std::io::Result<stif::Response>,
     ) -> std::io::Result<Quality> {
         let qualities = [Quality::Hard, Quality::Good, Quality::Easy];
         let user_feedback = interaction(vec![
             stif::Block::Paragraph(vec!["Correct! Choose difficulty:".into()]),
             stif::Block::one_of([
-                format!("Hard {}d", next_states.hard.interval),
-                format!("Good {}d", next_states.good.interval),
-                format!("Easy {}d", next_states.easy.interval),
+                format!("Hard {}", format_interval(next_intervals.hard)),
+                format!("Good {}", format_interval(next_intervals.good)),
+                format!("Easy {}", format_interval(next_intervals.easy)),
             ]),
         ])?;
         let i = stif::response_as_one_of(user_feedback.last().unwrap().to_owned())
@@ -82,22 +108,22 @@ impl StatelessTask {
 
     fn feedback_wrong(
         &self,
-        next_states: &fsrs::NextStates,
+        next_intervals: &NextIntervals,
         interaction: &mut impl FnMut(stif::Blocks) -> std::io::Result<stif::Response>,
     ) -> std::io::Result<Quality> {
         interaction(vec![stif::Block::Paragraph(vec![
             format!(
-                "Wrong. Next review in {}h",
-                next_states.again.interval * 24.
+                "Wrong. Next review in {}",
+                format_interval(next_intervals.again)
             )
             .into(),
         ])])?;
         Ok(Quality::Again)
     }
 
-    fn next_states(&self, weights: &Weights, desired_retention: f32) -> fsrs::NextStates {
+    fn next_intervals(&self, weights: &Weights, desired_retention: f64) -> NextIntervals {
         let now = chrono::Local::now();
         self.level
-            .next_states(&weights.fsrs(), desired_retention, now)
+            .next_intervals(&weights.fsrs(), weights.steps(), desired_retention, now)
     }
 }
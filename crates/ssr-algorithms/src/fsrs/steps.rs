@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+/// Short intervals, that task goes through before long-term scheduling.
+/// New tasks use learning steps, forgotten ones use relearning steps.
+/// Empty steps make task scheduled by FSRS straight away.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Steps {
+    pub learning: Vec<Duration>,
+    pub relearning: Vec<Duration>,
+}
+impl Steps {
+    /// No steps, as in decks created before steps were introduced.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            learning: Vec::new(),
+            relearning: Vec::new(),
+        }
+    }
+}
+/// Steps of newly created decks.
+impl Default for Steps {
+    fn default() -> Self {
+        Self {
+            learning: vec![
+                Duration::from_secs(MINUTE),
+                Duration::from_secs(10 * MINUTE),
+            ],
+            relearning: vec![Duration::from_secs(10 * MINUTE)],
+        }
+    }
+}
+
+/// Parses interval like `30s`, `10m`, `1h` or `2d`. Number without unit is minutes.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ssr_algorithms::fsrs::steps::{format_interval, parse_interval};
+/// assert_eq!(parse_interval("10m"), Some(Duration::from_secs(600)));
+/// assert_eq!(parse_interval("10"), Some(Duration::from_secs(600)));
+/// assert_eq!(parse_interval("1w"), None);
+/// assert_eq!(format_interval(parse_interval("1h").unwrap()), "1h");
+/// ```
+#[must_use]
+pub fn parse_interval(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let (number, unit) = input.split_at(
+        input
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(input.len()),
+    );
+    let number = number.parse::<u64>().ok()?;
+    let unit = match unit {
+        "s" => 1,
+        "" | "m" => MINUTE,
+        "h" => HOUR,
+        "d" => DAY,
+        _ => return None,
+    };
+    Some(Duration::from_secs(number.checked_mul(unit)?))
+}
+
+/// Formats interval in the largest unit, that fits it: minutes, hours or days.
+#[must_use]
+pub fn format_interval(interval: Duration) -> String {
+    let secs = interval.as_secs_f64();
+    #[allow(clippy::cast_precision_loss)]
+    let (minute, hour, day) = (MINUTE as f64, HOUR as f64, DAY as f64);
+    if secs < hour {
+        format!("{:.0}m", secs / minute)
+    } else if secs < day {
+        format!("{:.0}h", secs / hour)
+    } else {
+        format!("{:.0}d", secs / day)
+    }
+}
@@ -2,21 +2,126 @@ use fsrs::{FSRS, FSRSItem};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use super::Task;
+use super::{Task, steps::Steps};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+/// Version of FSRS model, that parameters were made for.
+/// Each version has its own number of parameters.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelVersion {
+    Fsrs4_5,
+    Fsrs5,
+    Fsrs6,
+}
+impl ModelVersion {
+    #[must_use]
+    pub fn from_len(len: usize) -> Option<Self> {
+        match len {
+            17 => Some(Self::Fsrs4_5),
+            19 => Some(Self::Fsrs5),
+            21 => Some(Self::Fsrs6),
+            _ => None,
+        }
+    }
+    #[must_use]
+    pub fn parameters_count(self) -> usize {
+        match self {
+            Self::Fsrs4_5 => 17,
+            Self::Fsrs5 => 19,
+            Self::Fsrs6 => 21,
+        }
+    }
+}
+impl std::fmt::Display for ModelVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Fsrs4_5 => "FSRS-4.5",
+            Self::Fsrs5 => "FSRS-5",
+            Self::Fsrs6 => "FSRS-6",
+        })
+    }
+}
+
+/// Parameters of FSRS model and learning steps of deck.
+///
+/// # Examples
+///
+/// ```
+/// use ssr_algorithms::fsrs::{steps::Steps, weights::Weights};
+///
+/// assert_eq!(Weights::default().steps(), &Steps::default());
+///
+/// let old = serde_json::json!({ "weights": Weights::default().parameters() });
+/// let weights: Weights = serde_json::from_value(old).unwrap();
+/// assert_eq!(weights.steps(), &Steps::none());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(try_from = "StoredWeights")]
 pub struct Weights {
-    weights: [f32; 19],
+    version: ModelVersion,
+    parameters: Vec<f32>,
+    steps: Steps,
+}
+/// Weights as they are stored. Old files have only `weights` array, its length tells the version.
+#[derive(Deserialize)]
+struct StoredWeights {
+    version: Option<ModelVersion>,
+    #[serde(alias = "weights")]
+    parameters: Vec<f32>,
+    /// Decks from before steps keep being scheduled without them.
+    #[serde(default = "Steps::none")]
+    steps: Steps,
+}
+impl TryFrom<StoredWeights> for Weights {
+    type Error = String;
+
+    fn try_from(stored: StoredWeights) -> Result<Self, Self::Error> {
+        let len = stored.parameters.len();
+        let version = match stored.version {
+            Some(version) if version.parameters_count() == len => version,
+            Some(version) => {
+                return Err(format!(
+                    "{version} expects {} parameters, got {len}",
+                    version.parameters_count()
+                ));
+            }
+            None => ModelVersion::from_len(len)
+                .ok_or_else(|| format!("{len} parameters match no FSRS version"))?,
+        };
+        // Otherwise every scheduling would fail, e.g. on parameters made by newer model.
+        FSRS::new(Some(&stored.parameters))
+            .map_err(|err| format!("installed FSRS can't use {version} parameters: {err}"))?;
+        Ok(Self {
+            version,
+            parameters: stored.parameters,
+            steps: stored.steps,
+        })
+    }
 }
 impl Weights {
+    /// Parameters are checked, when weights are loaded or optimized.
     pub fn fsrs(&self) -> FSRS {
-        FSRS::new(Some(&self.weights)).unwrap()
+        FSRS::new(Some(&self.parameters)).expect("parameters should be valid")
+    }
+    pub fn version(&self) -> ModelVersion {
+        self.version
+    }
+    pub fn parameters(&self) -> &[f32] {
+        &self.parameters
+    }
+    pub fn steps(&self) -> &Steps {
+        &self.steps
+    }
+    pub fn set_steps(&mut self, steps: Steps) {
+        self.steps = steps;
     }
 }
 impl Default for Weights {
     fn default() -> Self {
         Self {
-            weights: fsrs::DEFAULT_PARAMETERS,
+            version: ModelVersion::from_len(fsrs::DEFAULT_PARAMETERS.len())
+                .expect("fsrs library should use known model version"),
+            parameters: fsrs::DEFAULT_PARAMETERS.to_vec(),
+            steps: Steps::default(),
         }
     }
 }
@@ -41,6 +146,119 @@ fn extract_first_long_term_reviews<'a>(
         .collect()
 }
 
+/// Review histories of started tasks, that have long-term reviews.
+fn histories<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Vec<FSRSItem> {
+    tasks
+        .into_iter()
+        .filter_map(|t| t.level.history())
+        .collect()
+}
+
+/// Every this many histories one is held out of fitting to evaluate optimized weights on.
+const HELD_OUT_EVERY: usize = 5;
+
+/// Splits histories into ones to fit weights on and ones to evaluate them on.
+/// Histories are split in time order, histories reviewed last are held out,
+/// so weights are evaluated on reviews, that they would have to predict.
+fn split_held_out<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> (Vec<FSRSItem>, Vec<FSRSItem>) {
+    let mut histories = tasks
+        .into_iter()
+        .filter_map(|t| {
+            let last_review = t.level.past_reviews().iter().map(|r| r.timestamp).max()?;
+            Some((last_review, t.level.history()?))
+        })
+        .collect_vec();
+    histories.sort_by_key(|(last_review, _)| *last_review);
+    let mut fitted = histories
+        .into_iter()
+        .map(|(_, history)| history)
+        .collect_vec();
+    let held_out = fitted.split_off(fitted.len() - fitted.len() / HELD_OUT_EVERY);
+    (fitted, held_out)
+}
+
+/// How well parameters predict recall on review histories, lower is better.
+#[derive(Debug, Clone, Copy)]
+pub struct Evaluation {
+    pub log_loss: f32,
+    /// Root mean square error between predicted and actual recall, grouped into bins.
+    pub rmse: f32,
+}
+
+/// Current weights and weights optimized without held out histories evaluated on them,
+/// so optimized ones can be applied only if they are better.
+/// `weights` are optimized on all histories.
+#[derive(Debug, Clone)]
+pub struct OptimizationReport {
+    pub current: Evaluation,
+    pub optimized: Evaluation,
+    pub weights: Weights,
+}
+
+impl Weights {
+    /// # Errors
+    /// If there are no review histories to evaluate on.
+    pub fn evaluate<'a>(
+        &self,
+        tasks: impl IntoIterator<Item = &'a Task>,
+    ) -> Result<Evaluation, Box<dyn std::error::Error>> {
+        self.evaluate_histories(histories(tasks))
+    }
+    fn evaluate_histories(
+        &self,
+        histories: Vec<FSRSItem>,
+    ) -> Result<Evaluation, Box<dyn std::error::Error>> {
+        let evaluation = self.fsrs().evaluate(histories, |_| true)?;
+        Ok(Evaluation {
+            log_loss: evaluation.log_loss,
+            rmse: evaluation.rmse_bins,
+        })
+    }
+    /// Optimizes copy of weights, `self` isn't modified.
+    /// # Errors
+    /// If there are too few histories to hold out some of them, or optimization or evaluation fails.
+    pub fn optimization_report<'a>(
+        &self,
+        tasks: impl IntoIterator<Item = &'a Task>,
+    ) -> Result<OptimizationReport, Box<dyn std::error::Error>> {
+        let tasks = tasks.into_iter().collect_vec();
+        let (fitted, held_out) = split_held_out(tasks.iter().copied());
+        if held_out.is_empty() {
+            return Err(format!(
+                "at least {HELD_OUT_EVERY} tasks with long-term reviews are needed to evaluate optimization"
+            )
+            .into());
+        }
+        let mut evaluated = self.clone();
+        evaluated.optimize_histories(fitted)?;
+        let mut weights = self.clone();
+        weights.optimize_histories(histories(tasks))?;
+        Ok(OptimizationReport {
+            current: self.evaluate_histories(held_out.clone())?,
+            optimized: evaluated.evaluate_histories(held_out)?,
+            weights,
+        })
+    }
+    fn optimize_histories(
+        &mut self,
+        mut histories: Vec<FSRSItem>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        histories.extend(extract_first_long_term_reviews(&histories));
+        let fsrs = FSRS::new(None)?;
+        let best_params = fsrs.compute_parameters(histories, None, true)?;
+        let version = ModelVersion::from_len(best_params.len()).ok_or_else(|| {
+            format!(
+                "optimizer returned {} parameters, which match no FSRS version",
+                best_params.len()
+            )
+        })?;
+        FSRS::new(Some(&best_params))?;
+        self.version = version;
+        self.parameters = best_params;
+        Ok(())
+    }
+}
+
 impl ssr_core::task::SharedStateExt<'_, Task> for Weights {
     fn optimize<'b>(
         &mut self,
@@ -49,17 +267,6 @@ impl ssr_core::task::SharedStateExt<'_, Task> for Weights {
     where
         Task: 'b,
     {
-        let mut tasks = tasks
-            .into_iter()
-            .filter_map(|t| t.level.history())
-            .collect::<Vec<_>>();
-        tasks.extend(extract_first_long_term_reviews(&tasks));
-        let fsrs = FSRS::new(None)?;
-        let best_params: [f32; 19] = fsrs
-            .compute_parameters(tasks, None, true)?
-            .try_into()
-            .expect("fsrs library should return exactly '19' weights");
-        self.weights = best_params;
-        Ok(())
+        self.optimize_histories(histories(tasks))
     }
 }
@@ -3,7 +3,7 @@ use std::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
 use ssr_core::task::level::TaskLevel;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Level {
     pub(crate) group: u32,
     pub(crate) last_repetition_time: SystemTime,
@@ -1,17 +1,23 @@
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 use level::Level;
 use s_text_input_f::BlocksWithAnswer;
 use serde::{Deserialize, Serialize};
-use ssr_core::task::{Task, level::TaskLevel};
+use ssr_core::{
+    review_log::Review,
+    task::{Grade, PastReview, Task, level::TaskLevel},
+};
 
 mod level;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WriteAnswer {
     level: Level,
     input_blocks: s_text_input_f::Blocks,
     correct_answer: s_text_input_f::Response,
+    /// Level doesn't keep reviews, so they are stored to migrate to other algorithm.
+    #[serde(default)]
+    history: Vec<PastReview>,
 }
 
 impl WriteAnswer {
@@ -24,6 +30,7 @@ impl WriteAnswer {
             level: Level::default(),
             input_blocks,
             correct_answer,
+            history: Vec::new(),
         }
     }
 }
@@ -35,6 +42,10 @@ impl Task<'_> for WriteAnswer {
         self.level.next_repetition(shared, 0.)
     }
 
+    fn is_new(&self) -> bool {
+        self.level.last_repetition_time == SystemTime::UNIX_EPOCH
+    }
+
     fn complete(
         &mut self,
         (): &mut (),
@@ -42,9 +53,15 @@ impl Task<'_> for WriteAnswer {
         interaction: &mut impl FnMut(
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> std::io::Result<()> {
+    ) -> std::io::Result<Review> {
+        let timestamp = SystemTime::now();
+        let state_before = Review::snapshot(&self.level);
+        let was_new = self.is_new();
+        let started = Instant::now();
         let user_answer = interaction(self.input_blocks.clone())?;
-        if s_text_input_f::eq_response(&user_answer, &self.correct_answer, true, false) {
+        let elapsed = started.elapsed();
+        let correct = s_text_input_f::eq_response(&user_answer, &self.correct_answer, true, false);
+        if correct {
             let feedback = vec![
                 s_text_input_f::Block::Paragraph(vec![s_text_input_f::ParagraphItem::Text(
                     "All answers correct!".to_string(),
@@ -52,11 +69,10 @@ impl Task<'_> for WriteAnswer {
                 s_text_input_f::Block::OneOf(vec!["OK".to_string()]),
             ];
             interaction(feedback)?;
-            self.level.update(&mut (), (SystemTime::now(), true));
         } else {
             let mut feedback = s_text_input_f::to_answered(
                 self.input_blocks.clone(),
-                user_answer,
+                user_answer.clone(),
                 self.correct_answer.clone(),
             )
             .into_iter()
@@ -65,9 +81,24 @@ impl Task<'_> for WriteAnswer {
             feedback.push(s_text_input_f::Block::Paragraph(vec![]));
             feedback.push(s_text_input_f::Block::OneOf(vec!["OK".to_string()]));
             interaction(feedback)?;
-            self.level.update(&mut (), (SystemTime::now(), false));
         }
-        Ok(())
+        self.level.update(&mut (), (SystemTime::now(), correct));
+        let grade = if correct { Grade::Good } else { Grade::Again };
+        self.history.push(PastReview { timestamp, grade });
+        Ok(Review {
+            timestamp,
+            grade,
+            recalled: (!was_new).then_some(correct),
+            elapsed,
+            response: user_answer,
+            interval: self
+                .level
+                .next_repetition(&(), 0.)
+                .duration_since(timestamp)
+                .unwrap_or_default(),
+            state_before,
+            state_after: Review::snapshot(&self.level),
+        })
     }
 
     fn new(input: s_text_input_f::BlocksWithAnswer) -> Self {
@@ -75,6 +106,7 @@ impl Task<'_> for WriteAnswer {
             level: Level::default(),
             input_blocks: input.blocks,
             correct_answer: input.answer,
+            history: Vec::new(),
         }
     }
 
@@ -84,4 +116,21 @@ impl Task<'_> for WriteAnswer {
             answer: self.correct_answer.clone(),
         }
     }
+
+    fn update_blocks(&mut self, input: s_text_input_f::BlocksWithAnswer) {
+        self.input_blocks = input.blocks;
+        self.correct_answer = input.answer;
+    }
+
+    fn history(&self) -> Vec<PastReview> {
+        self.history.clone()
+    }
+
+    fn replay(&mut self, (): &mut (), reviews: &[PastReview]) {
+        for review in reviews {
+            self.level
+                .update(&mut (), (review.timestamp, review.grade.is_correct()));
+            self.history.push(*review);
+        }
+    }
 }
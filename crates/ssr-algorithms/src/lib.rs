@@ -1,3 +1,4 @@
+pub mod any;
 pub mod fsrs;
 pub mod leitner_system;
 pub mod super_memory_2;
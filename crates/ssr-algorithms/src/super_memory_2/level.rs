@@ -5,9 +5,9 @@ use std::{
 };
 
 use serde::{Deserialize, Serialize};
-use ssr_core::task::level::TaskLevel;
+use ssr_core::task::{Grade, level::TaskLevel};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Level {
     e_factor: f64,
     strike: u32,
@@ -54,6 +54,36 @@ impl Display for Quality {
     }
 }
 
+impl From<Quality> for Grade {
+    fn from(quality: Quality) -> Self {
+        match quality {
+            Quality::CompleteBlackout
+            | Quality::IncorrectResponseButCorrectRemembered
+            | Quality::IncorrectResponseAndSeemedEasyToRecall => Self::Again,
+            Quality::CorrectResponseRecalledWithSeriousDifficulty => Self::Hard,
+            Quality::CorrectResponseAfterHesitation => Self::Good,
+            Quality::PerfectResponse => Self::Easy,
+        }
+    }
+}
+impl From<Grade> for Quality {
+    fn from(grade: Grade) -> Self {
+        match grade {
+            Grade::Again => Self::IncorrectResponseButCorrectRemembered,
+            Grade::Hard => Self::CorrectResponseRecalledWithSeriousDifficulty,
+            Grade::Good => Self::CorrectResponseAfterHesitation,
+            Grade::Easy => Self::PerfectResponse,
+        }
+    }
+}
+
+impl Level {
+    /// Interval is set by the first repetition.
+    pub(crate) fn is_new(&self) -> bool {
+        self.interval.is_zero()
+    }
+}
+
 impl TaskLevel<'_> for Level {
     type SharedState = ();
     type Context = (SystemTime, Quality);
@@ -1,17 +1,23 @@
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 use level::{Level, Quality};
 use s_text_input_f::{BlocksWithAnswer, ParagraphItem};
 use serde::{Deserialize, Serialize};
-use ssr_core::task::{Task, level::TaskLevel};
+use ssr_core::{
+    review_log::Review,
+    task::{Grade, PastReview, Task, level::TaskLevel},
+};
 
 mod level;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WriteAnswer {
     level: Level,
     input_blocks: s_text_input_f::Blocks,
     correct_answer: s_text_input_f::Response,
+    /// Level doesn't keep reviews, so they are stored to migrate to other algorithm.
+    #[serde(default)]
+    history: Vec<PastReview>,
 }
 
 impl WriteAnswer {
@@ -24,6 +30,7 @@ impl WriteAnswer {
             level: Level::default(),
             input_blocks,
             correct_answer,
+            history: Vec::new(),
         }
     }
 
@@ -76,6 +83,10 @@ impl Task<'_> for WriteAnswer {
         self.level.next_repetition(shared, retrievability_goal)
     }
 
+    fn is_new(&self) -> bool {
+        self.level.is_new()
+    }
+
     fn complete(
         &mut self,
         (): &mut (),
@@ -83,54 +94,74 @@ impl Task<'_> for WriteAnswer {
         interaction: &mut impl FnMut(
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> std::io::Result<()> {
+    ) -> std::io::Result<Review> {
+        let timestamp = SystemTime::now();
+        let state_before = Review::snapshot(&self.level);
+        let was_new = self.is_new();
+        let started = Instant::now();
         let user_answer = interaction(self.input_blocks.clone())?;
-        if s_text_input_f::eq_response(&user_answer, &self.correct_answer, true, false) {
-            const QUALITIES: [Quality; 3] = [
-                Quality::CorrectResponseRecalledWithSeriousDifficulty,
-                Quality::CorrectResponseAfterHesitation,
-                Quality::PerfectResponse,
-            ];
-            let qualities_strings = vec![
-                "recalled with serious difficulty".to_string(),
-                "correct, but after hesitation".to_string(),
-                "perfect response".to_string(),
-            ];
-            let directive = "All answers correct! Choose difficulty:".to_string();
-
-            let quality = self.get_feedback(
-                user_answer,
-                directive,
-                qualities_strings,
-                interaction,
-                QUALITIES,
-            )?;
-
-            self.level.update(&mut (), (SystemTime::now(), quality));
-        } else {
-            const QUALITIES: [Quality; 3] = [
-                Quality::CompleteBlackout,
-                Quality::IncorrectResponseButCorrectRemembered,
-                Quality::IncorrectResponseAndSeemedEasyToRecall,
-            ];
-            let qualities_strings = vec![
-                "complete blackout".to_string(),
-                "incorrect response, but correct remembered".to_string(),
-                "incorrect response, but seemed easy to recall".to_string(),
-            ];
-            let directive = "Choose difficulty:".to_string();
-
-            let quality = self.get_feedback(
-                user_answer,
-                directive,
-                qualities_strings,
-                interaction,
-                QUALITIES,
-            )?;
-
-            self.level.update(&mut (), (SystemTime::now(), quality));
-        }
-        Ok(())
+        let elapsed = started.elapsed();
+        let quality =
+            if s_text_input_f::eq_response(&user_answer, &self.correct_answer, true, false) {
+                const QUALITIES: [Quality; 3] = [
+                    Quality::CorrectResponseRecalledWithSeriousDifficulty,
+                    Quality::CorrectResponseAfterHesitation,
+                    Quality::PerfectResponse,
+                ];
+                let qualities_strings = vec![
+                    "recalled with serious difficulty".to_string(),
+                    "correct, but after hesitation".to_string(),
+                    "perfect response".to_string(),
+                ];
+                let directive = "All answers correct! Choose difficulty:".to_string();
+
+                self.get_feedback(
+                    user_answer.clone(),
+                    directive,
+                    qualities_strings,
+                    interaction,
+                    QUALITIES,
+                )?
+            } else {
+                const QUALITIES: [Quality; 3] = [
+                    Quality::CompleteBlackout,
+                    Quality::IncorrectResponseButCorrectRemembered,
+                    Quality::IncorrectResponseAndSeemedEasyToRecall,
+                ];
+                let qualities_strings = vec![
+                    "complete blackout".to_string(),
+                    "incorrect response, but correct remembered".to_string(),
+                    "incorrect response, but seemed easy to recall".to_string(),
+                ];
+                let directive = "Choose difficulty:".to_string();
+
+                self.get_feedback(
+                    user_answer.clone(),
+                    directive,
+                    qualities_strings,
+                    interaction,
+                    QUALITIES,
+                )?
+            };
+        self.level.update(&mut (), (SystemTime::now(), quality));
+        self.history.push(PastReview {
+            timestamp,
+            grade: quality.into(),
+        });
+        Ok(Review {
+            timestamp,
+            grade: quality.into(),
+            recalled: (!was_new).then_some(Grade::from(quality).is_correct()),
+            elapsed,
+            response: user_answer,
+            interval: self
+                .level
+                .next_repetition(&(), 0.)
+                .duration_since(timestamp)
+                .unwrap_or_default(),
+            state_before,
+            state_after: Review::snapshot(&self.level),
+        })
     }
 
     fn new(input: s_text_input_f::BlocksWithAnswer) -> Self {
@@ -138,6 +169,7 @@ impl Task<'_> for WriteAnswer {
             level: Level::default(),
             input_blocks: input.blocks,
             correct_answer: input.answer,
+            history: Vec::new(),
         }
     }
 
@@ -147,4 +179,21 @@ impl Task<'_> for WriteAnswer {
             answer: self.correct_answer.clone(),
         }
     }
+
+    fn update_blocks(&mut self, input: s_text_input_f::BlocksWithAnswer) {
+        self.input_blocks = input.blocks;
+        self.correct_answer = input.answer;
+    }
+
+    fn history(&self) -> Vec<PastReview> {
+        self.history.clone()
+    }
+
+    fn replay(&mut self, (): &mut (), reviews: &[PastReview]) {
+        for review in reviews {
+            self.level
+                .update(&mut (), (review.timestamp, review.grade.into()));
+            self.history.push(*review);
+        }
+    }
 }
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use ssr_algorithms::fsrs::{Task, anki, steps::Steps, weights::Weights};
+use ssr_core::task::Task as _;
+
+const SAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/sample.apkg");
+
+/// Time from the last review of `task` until its next repetition.
+fn next_interval(task: &Task, steps: Steps) -> Duration {
+    let mut weights = Weights::default();
+    weights.set_steps(steps);
+    let last_review = task.history().last().unwrap().timestamp;
+    task.next_repetition(&weights, 0.9)
+        .duration_since(last_review)
+        .unwrap()
+}
+
+#[test]
+fn imports_every_card_with_its_reviews() {
+    let tasks = anki::import(SAMPLE, &Steps::default()).unwrap();
+    let history = tasks
+        .iter()
+        .map(|task| task.history().len())
+        .collect::<Vec<_>>();
+    // manual reschedule of the second card isn't review
+    assert_eq!(history, [3, 2, 0, 0, 0]);
+}
+
+#[test]
+fn forgotten_card_relearns_with_deck_steps() {
+    let steps = Steps {
+        learning: Vec::new(),
+        relearning: vec![Duration::from_secs(5 * 60)],
+    };
+    let tasks = anki::import(SAMPLE, &steps).unwrap();
+    assert_eq!(next_interval(&tasks[0], steps), Duration::from_secs(5 * 60));
+
+    let tasks = anki::import(SAMPLE, &Steps::none()).unwrap();
+    assert!(next_interval(&tasks[0], Steps::none()) >= Duration::from_secs(24 * 60 * 60));
+}
@@ -0,0 +1,51 @@
+use std::time::{Duration, SystemTime};
+
+use s_text_input_f::BlocksWithAnswer;
+use ssr_algorithms::any::{Algorithm, State, Task};
+use ssr_core::task::{Grade, PastReview, Task as _};
+
+const ALGORITHMS: [Algorithm; 3] = [Algorithm::Fsrs, Algorithm::SuperMemory2, Algorithm::Leitner];
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn state(algorithm: Algorithm) -> State {
+    let mut state = State::default();
+    state.set_algorithm(algorithm);
+    state
+}
+
+/// Task of `algorithm`, that was reviewed on three different days.
+fn reviewed(algorithm: Algorithm) -> Task {
+    let mut task = Task::new(BlocksWithAnswer {
+        blocks: Vec::new(),
+        answer: Vec::new(),
+    });
+    let start = SystemTime::now() - 30 * DAY;
+    let reviews =
+        [(0, Grade::Good), (3, Grade::Again), (4, Grade::Good)].map(|(day, grade)| PastReview {
+            timestamp: start + day * DAY,
+            grade,
+        });
+    task.replay(&mut state(algorithm), &reviews);
+    task
+}
+
+#[test]
+fn migrated_task_keeps_history_and_stays_started() {
+    for from in ALGORITHMS {
+        let task = reviewed(from);
+        assert_eq!(task.algorithm(), Some(from));
+        assert_eq!(task.history().len(), 3, "{from}");
+        for to in ALGORITHMS {
+            let mut state = state(to);
+            let migrated = task.migrate(&mut state);
+            assert_eq!(migrated.algorithm(), Some(to), "{from} to {to}");
+            assert_eq!(migrated.history().len(), 3, "{from} to {to}");
+            assert!(!migrated.is_new(), "{from} to {to}");
+            assert_ne!(
+                migrated.next_repetition(&state, 0.9),
+                SystemTime::UNIX_EPOCH,
+                "{from} to {to}"
+            );
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::{Duration, SystemTime};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use ssr_algorithms::fsrs::{Task, weights::Weights};
+use ssr_core::task::{Grade, PastReview, Task as _};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Deck, where each task was reviewed on growing intervals
+/// and recalled with probability predicted by default weights.
+fn reviewed_deck(tasks: u32) -> Vec<Task> {
+    let mut weights = Weights::default();
+    let mut rng = StdRng::seed_from_u64(0);
+    let start = SystemTime::UNIX_EPOCH + 20_000 * DAY;
+    (0..tasks)
+        .map(|i| {
+            let mut task = Task::new(Vec::new(), Vec::new(), Vec::new());
+            let mut time = start + (i % 60) * DAY;
+            let mut grade = Grade::Good;
+            for interval in [1, 3, 7, 15, 30, 60] {
+                task.replay(
+                    &mut weights,
+                    &[PastReview {
+                        timestamp: time,
+                        grade,
+                    }],
+                );
+                time += interval * DAY;
+                let recall = task.retrievability(&weights, time).unwrap();
+                grade = if rng.random_bool(recall) {
+                    Grade::Good
+                } else {
+                    Grade::Again
+                };
+            }
+            task
+        })
+        .collect()
+}
+
+#[test]
+fn optimization_is_evaluated_on_held_out_histories() {
+    let deck = reviewed_deck(500);
+    let report = Weights::default().optimization_report(&deck).unwrap();
+    println!("{report:#?}");
+    for evaluation in [report.current, report.optimized] {
+        assert!(evaluation.log_loss.is_finite() && evaluation.log_loss > 0.);
+        assert!((0. ..1.).contains(&evaluation.rmse));
+    }
+    assert_ne!(report.weights.parameters(), Weights::default().parameters());
+}
+
+#[test]
+fn optimization_needs_histories_to_hold_out() {
+    let deck = reviewed_deck(4);
+    assert!(Weights::default().optimization_report(&deck).is_err());
+}
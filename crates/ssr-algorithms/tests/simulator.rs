@@ -0,0 +1,39 @@
+use ssr_algorithms::fsrs::{
+    simulator::{RETENTIONS, SimulationConfig, optimal_retention, retention_curve, simulate},
+    weights::Weights,
+};
+
+#[test]
+fn same_seed_gives_same_simulation() {
+    let fsrs = Weights::default().fsrs();
+    let config = SimulationConfig {
+        seed: 7,
+        ..SimulationConfig::for_deck(100, Some(10))
+    };
+    let first = simulate(&fsrs, &config, 0.9);
+    let second = simulate(&fsrs, &config, 0.9);
+    assert_eq!(first.daily_cost, second.daily_cost);
+    assert_eq!(first.memorized, second.memorized);
+    assert!(first.memorized > 0.);
+}
+
+#[test]
+fn optimal_retention_is_simulated_one() {
+    let curve = retention_curve(
+        &Weights::default().fsrs(),
+        &SimulationConfig::for_deck(100, Some(10)),
+    );
+    assert_eq!(curve.len(), RETENTIONS.count());
+    let optimal = optimal_retention(&curve).unwrap();
+    let percent = (optimal.desired_retention * 100.).round();
+    assert!(RETENTIONS.map(f64::from).any(|x| x == percent));
+}
+
+#[test]
+fn empty_deck_gets_no_recommendation() {
+    let curve = retention_curve(
+        &Weights::default().fsrs(),
+        &SimulationConfig::for_deck(0, None),
+    );
+    assert!(optimal_retention(&curve).is_none());
+}
@@ -1,5 +1,8 @@
 #![warn(clippy::pedantic)]
 
+pub mod review_log;
+pub mod storage;
+pub mod tags;
 pub mod task;
 pub mod tasks_facade;
 
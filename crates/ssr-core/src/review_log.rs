@@ -0,0 +1,112 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+
+use crate::{task::Grade, tasks_facade::TaskId};
+
+/// Outcome of single [`Task::complete`](crate::task::Task::complete).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Review {
+    /// When task was shown.
+    pub timestamp: SystemTime,
+    /// Rating chosen by user, in the same scale for all algorithms.
+    #[serde(alias = "rating", deserialize_with = "grade_or_rating")]
+    pub grade: Grade,
+    /// Whether started task was recalled, in the same sense for all algorithms.
+    /// `None` for the first review of new task and in reviews logged before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recalled: Option<bool>,
+    /// Time user spent answering, feedback form is not included.
+    pub elapsed: Duration,
+    pub response: s_text_input_f::Response,
+    /// Time from `timestamp` until next repetition.
+    pub interval: Duration,
+    /// Algorithm specific task state, see [`Review::snapshot`].
+    pub state_before: serde_json::Value,
+    pub state_after: serde_json::Value,
+}
+
+/// Reviews logged before grades were recorded have FSRS rating (`1` is again ... `4` is easy)
+/// instead, because only FSRS decks could be reviewed then.
+fn grade_or_rating<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Grade, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Grade(Grade),
+        Rating(u32),
+    }
+    match Stored::deserialize(deserializer)? {
+        Stored::Grade(grade) => Ok(grade),
+        Stored::Rating(1) => Ok(Grade::Again),
+        Stored::Rating(2) => Ok(Grade::Hard),
+        Stored::Rating(3) => Ok(Grade::Good),
+        Stored::Rating(4) => Ok(Grade::Easy),
+        Stored::Rating(rating) => Err(D::Error::custom(format!("unknown rating {rating}"))),
+    }
+}
+
+impl Review {
+    /// Serializes algorithm state for `state_before` and `state_after`.
+    pub fn snapshot(state: &impl Serialize) -> serde_json::Value {
+        serde_json::to_value(state).unwrap_or_default()
+    }
+}
+
+/// Record of append-only review log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReviewLogEntry {
+    pub task_id: TaskId,
+    #[serde(flatten)]
+    pub review: Review,
+}
+
+/// Cancels the latest review of `task_id`, that isn't canceled yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Undo {
+    pub task_id: TaskId,
+    pub timestamp: SystemTime,
+}
+
+/// Review log record as it's stored.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum Record {
+    Review(ReviewLogEntry),
+    Undo(Undo),
+}
+
+/// Applies undo records, leaving only reviews, that are in effect.
+pub(crate) fn effective(records: impl IntoIterator<Item = Record>) -> Vec<ReviewLogEntry> {
+    let mut reviews: Vec<ReviewLogEntry> = Vec::new();
+    for record in records {
+        match record {
+            Record::Review(entry) => reviews.push(entry),
+            Record::Undo(undo) => {
+                if let Some(index) = reviews.iter().rposition(|x| x.task_id == undo.task_id) {
+                    reviews.remove(index);
+                }
+            }
+        }
+    }
+    reviews
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_rating_is_read_as_grade() {
+        let entry = |grade: &str| {
+            serde_json::from_str::<ReviewLogEntry>(&format!(
+                r#"{{"task_id": 1, "timestamp": {{"secs_since_epoch": 0, "nanos_since_epoch": 0}},
+                   {grade}, "elapsed": {{"secs": 1, "nanos": 0}}, "response": [],
+                   "interval": {{"secs": 60, "nanos": 0}}, "state_before": null, "state_after": null}}"#
+            ))
+            .map(|entry| entry.review.grade)
+        };
+        assert_eq!(entry(r#""rating": 1"#).unwrap(), Grade::Again);
+        assert_eq!(entry(r#""rating": 4"#).unwrap(), Grade::Easy);
+        assert_eq!(entry(r#""grade": "Hard""#).unwrap(), Grade::Hard);
+        assert!(entry(r#""rating": 5"#).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    cell::Cell,
+    fs::OpenOptions,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Storage};
+use crate::review_log::{self, Record, ReviewLogEntry, Undo};
+
+/// JSON file, that is never left half-written.
+///
+/// New content is written to temporary file in the same directory, flushed to disk and then
+/// atomically renamed over the old one. Before the first save of each `JsonFile` old content is
+/// copied to rotating backups `<path>.1` (newest) ... `<path>.<backups>` (oldest), so backups keep
+/// previous sessions instead of previous saves.
+///
+/// # Examples
+///
+/// ```
+/// use ssr_core::storage::JsonFile;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("storage.json");
+///
+/// let mut buffer = String::new();
+/// assert_eq!(JsonFile::new(&path).load::<Vec<u32>>(&mut buffer).unwrap(), None);
+///
+/// for session in 0..4 {
+///     let file = JsonFile::new(&path).with_backups(2);
+///     file.save(&vec![session]).unwrap();
+///     file.save(&vec![session]).unwrap();
+/// }
+/// let file = JsonFile::new(&path).with_backups(2);
+/// let mut buffer = String::new();
+/// assert_eq!(file.load::<Vec<u32>>(&mut buffer).unwrap(), Some(vec![3]));
+/// let mut buffer = String::new();
+/// let backup = JsonFile::new(file.backup_path(1));
+/// assert_eq!(backup.load::<Vec<u32>>(&mut buffer).unwrap(), Some(vec![2]));
+/// let mut buffer = String::new();
+/// let backup = JsonFile::new(file.backup_path(2));
+/// assert_eq!(backup.load::<Vec<u32>>(&mut buffer).unwrap(), Some(vec![1]));
+/// assert!(!file.backup_path(3).exists());
+/// ```
+///
+/// Write interrupted before rename leaves only stray temporary file behind,
+/// while interrupted in-place write (as done by old versions) is detected instead of silently
+/// loaded:
+///
+/// ```
+/// use ssr_core::storage::{Error, JsonFile};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("storage.json");
+/// let file = JsonFile::new(&path);
+/// file.save(&vec![1, 2, 3]).unwrap();
+///
+/// std::fs::write(dir.path().join(".storage.json.interrupted.tmp"), "[1, 2").unwrap();
+/// let mut buffer = String::new();
+/// assert_eq!(file.load::<Vec<u32>>(&mut buffer).unwrap(), Some(vec![1, 2, 3]));
+///
+/// let file = JsonFile::new(&path);
+/// file.save(&vec![4, 5, 6]).unwrap();
+/// std::fs::write(&path, "[4, 5").unwrap();
+/// let mut buffer = String::new();
+/// assert!(matches!(
+///     file.load::<Vec<u32>>(&mut buffer),
+///     Err(Error::Corrupted { .. })
+/// ));
+/// let mut buffer = String::new();
+/// let backup = JsonFile::new(file.backup_path(1));
+/// assert_eq!(backup.load::<Vec<u32>>(&mut buffer).unwrap(), Some(vec![1, 2, 3]));
+/// ```
+///
+/// Review log is appended to separate file, and record interrupted while appending is dropped.
+/// Undone reviews are not returned:
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use ssr_core::{
+///     review_log::{Review, ReviewLogEntry, Undo},
+///     storage::JsonFile,
+///     task::Grade,
+/// };
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let file = JsonFile::new(dir.path().join("storage.json"));
+/// let entry = |task_id| ReviewLogEntry {
+///     task_id,
+///     review: Review {
+///         timestamp: SystemTime::UNIX_EPOCH,
+///         grade: Grade::Good,
+///         recalled: Some(true),
+///         elapsed: Duration::from_secs(5),
+///         response: vec![vec!["answer".into()]],
+///         interval: Duration::from_secs(86400),
+///         state_before: Review::snapshot(&0),
+///         state_after: Review::snapshot(&1),
+///     },
+/// };
+///
+/// file.append_review(&entry(u128::MAX)).unwrap();
+/// file.append_review(&entry(2)).unwrap();
+/// assert_eq!(file.reviews().unwrap(), [entry(u128::MAX), entry(2)]);
+///
+/// use std::io::Write;
+/// let mut log = std::fs::OpenOptions::new()
+///     .append(true)
+///     .open(file.review_log_path())
+///     .unwrap();
+/// write!(log, "{{\"task_id\":3,\"times").unwrap();
+/// assert_eq!(file.reviews().unwrap(), [entry(u128::MAX), entry(2)]);
+///
+/// file.append_review(&entry(4)).unwrap();
+/// assert_eq!(file.reviews().unwrap(), [entry(u128::MAX), entry(2), entry(4)]);
+///
+/// file.append_review(&entry(2)).unwrap();
+/// let undo = Undo {
+///     task_id: 2,
+///     timestamp: SystemTime::UNIX_EPOCH,
+/// };
+/// file.append_undo(&undo).unwrap();
+/// file.append_undo(&undo).unwrap();
+/// assert_eq!(file.reviews().unwrap(), [entry(u128::MAX), entry(4)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonFile {
+    path: PathBuf,
+    backups: usize,
+    backed_up: Cell<bool>,
+}
+
+impl JsonFile {
+    pub const DEFAULT_BACKUPS: usize = 3;
+
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            backups: Self::DEFAULT_BACKUPS,
+            backed_up: Cell::new(false),
+        }
+    }
+    /// Set number of kept backups. `0` disables them.
+    #[must_use]
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Path of `n`-th backup, `1` is the newest one.
+    #[must_use]
+    pub fn backup_path(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    /// Path of append-only review log, stored next to the file as JSON Lines.
+    #[must_use]
+    pub fn review_log_path(&self) -> PathBuf {
+        self.path.with_extension("revlog.jsonl")
+    }
+
+    /// Reads file into `buffer` and deserializes it.
+    ///
+    /// Returns `None` if file doesn't exist.
+    /// # Errors
+    /// - `Error::Corrupted` if file can't be parsed
+    /// - `Error::IO` if file can't be read
+    pub fn load<'a, T: Deserialize<'a>>(&self, buffer: &'a mut String) -> Result<Option<T>, Error> {
+        if !std::fs::exists(&self.path)? {
+            return Ok(None);
+        }
+        *buffer = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(buffer)
+            .map(Some)
+            .map_err(|source| Error::Corrupted {
+                path: self.path.clone(),
+                source,
+            })
+    }
+
+    /// # Errors
+    /// If serialization or any file operation fails.
+    /// File at `path` is either old or new version, never partially written.
+    pub fn save<T: Serialize>(&self, value: &T) -> Result<(), Error> {
+        let content = serde_json::to_string_pretty(value).map_err(Error::Serialize)?;
+        self.write(|file| file.write_all(content.as_bytes()))
+    }
+
+    /// Appends entry to review log, dropping record, that was interrupted while appending.
+    /// # Errors
+    /// If serialization or any file operation fails.
+    pub fn append_review(&self, entry: &ReviewLogEntry) -> Result<(), Error> {
+        self.append_record(&Record::Review(entry.clone()))
+    }
+    /// Appends undo of review to review log.
+    /// # Errors
+    /// If serialization or any file operation fails.
+    pub fn append_undo(&self, undo: &Undo) -> Result<(), Error> {
+        self.append_record(&Record::Undo(undo.clone()))
+    }
+    fn append_record(&self, record: &Record) -> Result<(), Error> {
+        let mut line = serde_json::to_string(record).map_err(Error::Serialize)?;
+        line.push('\n');
+        let mut log = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(self.review_log_path())?;
+        if log.metadata()?.len() > 0 {
+            let mut last = [0];
+            log.seek(SeekFrom::End(-1))?;
+            log.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                let mut content = Vec::new();
+                log.seek(SeekFrom::Start(0))?;
+                log.read_to_end(&mut content)?;
+                let complete = content
+                    .iter()
+                    .rposition(|&x| x == b'\n')
+                    .map_or(0, |x| x + 1);
+                log.set_len(complete as u64)?;
+            }
+        }
+        log.write_all(line.as_bytes())?;
+        log.sync_data()?;
+        Ok(())
+    }
+
+    /// Reads review log in order of appending, without undone reviews.
+    /// Record interrupted while appending is ignored.
+    /// # Errors
+    /// - `Error::Corrupted` if any complete record can't be parsed
+    /// - `Error::IO` if log can't be read
+    pub fn reviews(&self) -> Result<Vec<ReviewLogEntry>, Error> {
+        let path = self.review_log_path();
+        if !std::fs::exists(&path)? {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let complete = content.rfind('\n').map_or("", |end| &content[..end]);
+        let records = complete
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|source| Error::Corrupted {
+                    path: path.clone(),
+                    source,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(review_log::effective(records))
+    }
+
+    /// Writes file with content produced by `write_content`. If it fails, file is untouched.
+    fn write(
+        &self,
+        write_content: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+    ) -> Result<(), Error> {
+        super::write_atomically(&self.path, |temp| {
+            write_content(temp)?;
+            writeln!(temp)?;
+            // Only after content is written, so failed write doesn't touch backups.
+            if !self.backed_up.get() {
+                self.rotate_backups()?;
+                self.backed_up.set(true);
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    fn rotate_backups(&self) -> std::io::Result<()> {
+        if self.backups == 0 || !std::fs::exists(&self.path)? {
+            return Ok(());
+        }
+        for n in (1..self.backups).rev() {
+            let from = self.backup_path(n);
+            if std::fs::exists(&from)? {
+                std::fs::rename(from, self.backup_path(n + 1))?;
+            }
+        }
+        std::fs::copy(&self.path, self.backup_path(1))?;
+        Ok(())
+    }
+}
+
+/// Stores whole facade in one file, so every save rewrites it.
+impl<'a, F: Serialize + Deserialize<'a>> Storage<'a, F> for JsonFile {
+    fn is_empty(&mut self) -> Result<bool, Error> {
+        Ok(!std::fs::exists(&self.path)?)
+    }
+    fn load(&mut self, buffer: &'a mut String) -> Result<Option<F>, Error> {
+        JsonFile::load(self, buffer)
+    }
+    fn save(&mut self, facade: &F) -> Result<(), Error> {
+        JsonFile::save(self, facade)
+    }
+    fn append_review(&mut self, entry: &ReviewLogEntry) -> Result<(), Error> {
+        JsonFile::append_review(self, entry)
+    }
+    fn append_undo(&mut self, undo: &Undo) -> Result<(), Error> {
+        JsonFile::append_undo(self, undo)
+    }
+    fn reviews(&mut self) -> Result<Vec<ReviewLogEntry>, Error> {
+        JsonFile::reviews(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupted_write_keeps_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+        let file = JsonFile::new(&path);
+        file.save(&vec![1, 2, 3]).unwrap();
+        let original = std::fs::read(&path).unwrap();
+
+        let result = file.write(|temp| {
+            temp.write_all(b"[4, 5")?;
+            Err(std::io::Error::other("disk is full"))
+        });
+        assert!(matches!(result, Err(Error::IO(_))));
+        assert_eq!(std::fs::read(&path).unwrap(), original);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn backups_rotate_once_per_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+        JsonFile::new(&path).save(&0).unwrap();
+
+        let file = JsonFile::new(&path);
+        for i in 1..5 {
+            file.save(&i).unwrap();
+        }
+        let backup = |n| std::fs::read_to_string(file.backup_path(n)).ok();
+        assert_eq!(backup(1).as_deref(), Some("0\n"));
+        assert_eq!(backup(2), None);
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::{
+    review_log::{ReviewLogEntry, Undo},
+    tasks_facade::TaskId,
+};
+
+pub use json_file::JsonFile;
+#[cfg(feature = "sqlite")]
+pub use sqlite::Sqlite;
+
+mod json_file;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("storage file '{}' is corrupted: {source}", path.display())]
+    Corrupted {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("can't serialize storage: {0}")]
+    Serialize(serde_json::Error),
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+/// Place where tasks facade lives between runs.
+pub trait Storage<'a, F> {
+    /// # Errors
+    /// If storage can't be read.
+    fn is_empty(&mut self) -> Result<bool, Error>;
+    /// Returns `None` if nothing is stored yet.
+    /// `buffer` is used by backends, which deserialize `F` from single string.
+    /// # Errors
+    /// If storage can't be read or is corrupted.
+    fn load(&mut self, buffer: &'a mut String) -> Result<Option<F>, Error>;
+    /// Replaces everything stored with `facade`.
+    /// # Errors
+    /// If storage can't be written. Storage remains unmodified in that case.
+    fn save(&mut self, facade: &F) -> Result<(), Error>;
+    /// Saves changes of single task and of everything that is not a task.
+    ///
+    /// If there is no such task in `facade` it's removed from storage.
+    /// Backends, that can't write partially, save whole facade.
+    /// # Errors
+    /// If storage can't be written. Storage remains unmodified in that case.
+    fn save_task(&mut self, facade: &F, id: TaskId) -> Result<(), Error> {
+        let _ = id;
+        self.save(facade)
+    }
+    /// Saves reviewed task together with entry of review log, so they can't diverge.
+    ///
+    /// Backends without transactions save task first.
+    /// # Errors
+    /// If storage can't be written.
+    fn save_review(&mut self, facade: &F, entry: &ReviewLogEntry) -> Result<(), Error> {
+        self.save_task(facade, entry.task_id)?;
+        self.append_review(entry)
+    }
+    /// Saves task restored by undo together with record of undo, like [`Self::save_review`].
+    /// # Errors
+    /// If storage can't be written.
+    fn save_undo(&mut self, facade: &F, undo: &Undo) -> Result<(), Error> {
+        self.save_task(facade, undo.task_id)?;
+        self.append_undo(undo)
+    }
+    /// Appends entry to review log. Log is kept apart from facade and never rewritten.
+    /// # Errors
+    /// If storage can't be written.
+    fn append_review(&mut self, entry: &ReviewLogEntry) -> Result<(), Error>;
+    /// Appends record, that cancels the latest review of task.
+    /// # Errors
+    /// If storage can't be written.
+    fn append_undo(&mut self, undo: &Undo) -> Result<(), Error>;
+    /// Review log in order of appending, without undone reviews.
+    /// # Errors
+    /// If storage can't be read or is corrupted.
+    fn reviews(&mut self) -> Result<Vec<ReviewLogEntry>, Error>;
+}
+
+/// Tasks facade, that can be stored by parts.
+pub trait StoredFacade: Sized {
+    /// Everything except tasks.
+    type Header: Serialize + DeserializeOwned;
+    /// Task with its id and metadata.
+    type Record: Serialize + DeserializeOwned;
+
+    fn header(&self) -> Self::Header;
+    fn record(&self, id: TaskId) -> Option<&Self::Record>;
+    fn records(&self) -> impl Iterator<Item = (TaskId, &Self::Record)>;
+    fn from_parts(header: Self::Header, records: Vec<Self::Record>) -> Self;
+}
+
+/// Replaces file at `path` with content produced by `write_content`, so it's never left
+/// half-written: content is written to temporary file in the same directory, flushed to disk and
+/// then atomically renamed over `path`. If anything fails, file at `path` is untouched.
+/// # Errors
+/// If `write_content` or any file operation fails.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("notes.md");
+/// ssr_core::storage::write_atomically(&path, |file| file.write_all(b"old")).unwrap();
+///
+/// let failed = ssr_core::storage::write_atomically(&path, |file| {
+///     file.write_all(b"ne")?;
+///     Err(std::io::Error::other("disk is full"))
+/// });
+/// assert!(failed.is_err());
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+/// assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+/// ```
+pub fn write_atomically(
+    path: &Path,
+    write_content: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .map(|x| x.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut temp = tempfile::Builder::new()
+        .prefix(&format!(".{file_name}."))
+        .suffix(".tmp")
+        .tempfile_in(dir)?;
+    write_content(&mut temp)?;
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|err| err.error)?;
+    sync_dir(dir)
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    std::fs::File::open(dir)?.sync_all()
+}
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Copies facade and review log from `from` to `to`, if `to` is empty.
+///
+/// Returns whether anything was copied.
+/// # Errors
+/// If any of storages fails.
+pub fn migrate<'a, F>(
+    from: &mut dyn Storage<'a, F>,
+    to: &mut dyn Storage<'a, F>,
+    buffer: &'a mut String,
+) -> Result<bool, Error> {
+    if !to.is_empty()? {
+        return Ok(false);
+    }
+    let Some(facade) = from.load(buffer)? else {
+        return Ok(false);
+    };
+    if to.reviews()?.is_empty() {
+        for entry in from.reviews()? {
+            to.append_review(&entry)?;
+        }
+    }
+    to.save(&facade)?;
+    Ok(true)
+}
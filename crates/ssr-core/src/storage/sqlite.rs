@@ -0,0 +1,385 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use super::{Error, Storage, StoredFacade};
+use crate::{
+    review_log::{self, Record, ReviewLogEntry, Undo},
+    tasks_facade::TaskId,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS header (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    content TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS tasks (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS review_log (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    content TEXT NOT NULL
+);
+";
+
+/// Embedded `SQLite` database with one row per task, so saving single task doesn't rewrite whole
+/// facade. Once facade is loaded or saved, saving it again writes only changed rows.
+///
+/// # Examples
+///
+/// ```
+/// use ssr_core::{
+///     storage::{Sqlite, Storage, StoredFacade},
+///     tasks_facade::TaskId,
+/// };
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Deck {
+///     name: String,
+///     tasks: Vec<(TaskId, String)>,
+/// }
+/// impl StoredFacade for Deck {
+///     type Header = String;
+///     type Record = (TaskId, String);
+///     fn header(&self) -> String {
+///         self.name.clone()
+///     }
+///     fn record(&self, id: TaskId) -> Option<&(TaskId, String)> {
+///         self.tasks.iter().find(|x| x.0 == id)
+///     }
+///     fn records(&self) -> impl Iterator<Item = (TaskId, &(TaskId, String))> {
+///         self.tasks.iter().map(|x| (x.0, x))
+///     }
+///     fn from_parts(name: String, mut tasks: Vec<(TaskId, String)>) -> Self {
+///         tasks.sort();
+///         Self { name, tasks }
+///     }
+/// }
+///
+/// let mut storage = Sqlite::open_in_memory().unwrap();
+/// let mut buffer = String::new();
+/// assert_eq!(Storage::<Deck>::load(&mut storage, &mut buffer).unwrap(), None);
+///
+/// let mut deck = Deck {
+///     name: "deck".into(),
+///     tasks: vec![(1, "a".into()), (2, "b".into())],
+/// };
+/// storage.save(&deck).unwrap();
+///
+/// deck.tasks[0].1 = "changed".into();
+/// deck.tasks.remove(1);
+/// storage.save_task(&deck, 1).unwrap();
+/// storage.save_task(&deck, 2).unwrap();
+///
+/// let mut buffer = String::new();
+/// assert_eq!(storage.load(&mut buffer).unwrap(), Some(deck));
+/// ```
+pub struct Sqlite {
+    connection: Connection,
+    path: PathBuf,
+    /// Content of database as of the latest load or save, `None` before them.
+    stored: Option<Stored>,
+}
+
+/// Serialized header and tasks (by stringified id).
+struct Stored {
+    header: String,
+    tasks: HashMap<String, String>,
+}
+
+impl Sqlite {
+    /// Opens database, creating it if needed.
+    /// # Errors
+    /// If database can't be opened or has incompatible schema.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_owned();
+        Self::init(Connection::open(&path)?, path)
+    }
+    /// # Errors
+    /// If database can't be created.
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::init(Connection::open_in_memory()?, PathBuf::from(":memory:"))
+    }
+    fn init(connection: Connection, path: PathBuf) -> Result<Self, Error> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection,
+            path,
+            stored: None,
+        })
+    }
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn corrupted(&self) -> impl Fn(serde_json::Error) -> Error + '_ {
+        |source| Error::Corrupted {
+            path: self.path.clone(),
+            source,
+        }
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<String, Error> {
+    serde_json::to_string(value).map_err(Error::Serialize)
+}
+
+fn upsert_header(connection: &Connection, header: &str) -> Result<(), Error> {
+    connection.execute(
+        "INSERT INTO header (id, content) VALUES (0, ?1)
+         ON CONFLICT (id) DO UPDATE SET content = excluded.content",
+        params![header],
+    )?;
+    Ok(())
+}
+fn upsert_task(connection: &Connection, id: &str, record: &str) -> Result<(), Error> {
+    connection.execute(
+        "INSERT INTO tasks (id, content) VALUES (?1, ?2)
+         ON CONFLICT (id) DO UPDATE SET content = excluded.content",
+        params![id, record],
+    )?;
+    Ok(())
+}
+fn append_record(connection: &Connection, task_id: TaskId, record: &Record) -> Result<(), Error> {
+    connection.execute(
+        "INSERT INTO review_log (task_id, content) VALUES (?1, ?2)",
+        params![task_id.to_string(), to_json(record)?],
+    )?;
+    Ok(())
+}
+
+/// Writes single task (or removes it, if it isn't in `facade`) and header.
+///
+/// Returns what is written, to be remembered after commit.
+fn write_task<F: StoredFacade>(
+    connection: &Connection,
+    facade: &F,
+    id: TaskId,
+) -> Result<(String, Option<String>), Error> {
+    let header = to_json(&facade.header())?;
+    let record = facade.record(id).map(to_json).transpose()?;
+    match &record {
+        Some(record) => upsert_task(connection, &id.to_string(), record)?,
+        None => {
+            connection.execute("DELETE FROM tasks WHERE id = ?1", params![id.to_string()])?;
+        }
+    }
+    upsert_header(connection, &header)?;
+    Ok((header, record))
+}
+
+impl Sqlite {
+    fn remember_task(&mut self, id: TaskId, (header, record): (String, Option<String>)) {
+        if let Some(stored) = &mut self.stored {
+            stored.header = header;
+            match record {
+                Some(record) => stored.tasks.insert(id.to_string(), record),
+                None => stored.tasks.remove(&id.to_string()),
+            };
+        }
+    }
+}
+
+impl<F: StoredFacade> Storage<'_, F> for Sqlite {
+    fn is_empty(&mut self) -> Result<bool, Error> {
+        let header: Option<i64> = self
+            .connection
+            .query_row("SELECT id FROM header", [], |row| row.get(0))
+            .optional()?;
+        Ok(header.is_none())
+    }
+
+    fn load(&mut self, _buffer: &mut String) -> Result<Option<F>, Error> {
+        let header: Option<String> = self
+            .connection
+            .query_row("SELECT content FROM header", [], |row| row.get(0))
+            .optional()?;
+        let Some(header) = header else {
+            return Ok(None);
+        };
+        let mut statement = self.connection.prepare("SELECT id, content FROM tasks")?;
+        let tasks = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<String, String>, _>>()?;
+        drop(statement);
+        let records = tasks
+            .values()
+            .map(|content| serde_json::from_str(content).map_err(self.corrupted()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let facade = F::from_parts(
+            serde_json::from_str(&header).map_err(self.corrupted())?,
+            records,
+        );
+        self.stored = Some(Stored { header, tasks });
+        Ok(Some(facade))
+    }
+
+    fn save(&mut self, facade: &F) -> Result<(), Error> {
+        let header = to_json(&facade.header())?;
+        let tasks = facade
+            .records()
+            .map(|(id, record)| Ok((id.to_string(), to_json(record)?)))
+            .collect::<Result<HashMap<_, _>, Error>>()?;
+        let transaction = self.connection.transaction()?;
+        if let Some(stored) = &self.stored {
+            for id in stored.tasks.keys().filter(|id| !tasks.contains_key(*id)) {
+                transaction.execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
+            }
+            for (id, record) in &tasks {
+                if stored.tasks.get(id) != Some(record) {
+                    upsert_task(&transaction, id, record)?;
+                }
+            }
+            if stored.header != header {
+                upsert_header(&transaction, &header)?;
+            }
+        } else {
+            transaction.execute("DELETE FROM tasks", [])?;
+            for (id, record) in &tasks {
+                upsert_task(&transaction, id, record)?;
+            }
+            upsert_header(&transaction, &header)?;
+        }
+        transaction.commit()?;
+        self.stored = Some(Stored { header, tasks });
+        Ok(())
+    }
+
+    fn save_task(&mut self, facade: &F, id: TaskId) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+        let written = write_task(&transaction, facade, id)?;
+        transaction.commit()?;
+        self.remember_task(id, written);
+        Ok(())
+    }
+
+    fn save_review(&mut self, facade: &F, entry: &ReviewLogEntry) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+        let written = write_task(&transaction, facade, entry.task_id)?;
+        append_record(&transaction, entry.task_id, &Record::Review(entry.clone()))?;
+        transaction.commit()?;
+        self.remember_task(entry.task_id, written);
+        Ok(())
+    }
+
+    fn save_undo(&mut self, facade: &F, undo: &Undo) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+        let written = write_task(&transaction, facade, undo.task_id)?;
+        append_record(&transaction, undo.task_id, &Record::Undo(undo.clone()))?;
+        transaction.commit()?;
+        self.remember_task(undo.task_id, written);
+        Ok(())
+    }
+
+    fn append_review(&mut self, entry: &ReviewLogEntry) -> Result<(), Error> {
+        append_record(
+            &self.connection,
+            entry.task_id,
+            &Record::Review(entry.clone()),
+        )
+    }
+
+    fn append_undo(&mut self, undo: &Undo) -> Result<(), Error> {
+        append_record(&self.connection, undo.task_id, &Record::Undo(undo.clone()))
+    }
+
+    fn reviews(&mut self) -> Result<Vec<ReviewLogEntry>, Error> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT content FROM review_log ORDER BY seq")?;
+        let records = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|content| -> Result<Record, Error> {
+                serde_json::from_str(&content?).map_err(self.corrupted())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(review_log::effective(records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::{review_log::Review, task::Grade};
+
+    /// Deck with header `0` and tasks, that are their own ids.
+    #[derive(Debug, PartialEq)]
+    struct Deck(Vec<TaskId>);
+    impl StoredFacade for Deck {
+        type Header = u8;
+        type Record = TaskId;
+        fn header(&self) -> u8 {
+            0
+        }
+        fn record(&self, id: TaskId) -> Option<&TaskId> {
+            self.0.iter().find(|&&x| x == id)
+        }
+        fn records(&self) -> impl Iterator<Item = (TaskId, &TaskId)> {
+            self.0.iter().map(|x| (*x, x))
+        }
+        fn from_parts(_: u8, mut tasks: Vec<TaskId>) -> Self {
+            tasks.sort_unstable();
+            Self(tasks)
+        }
+    }
+
+    #[test]
+    fn save_writes_only_changed_rows() {
+        let mut storage = Sqlite::open_in_memory().unwrap();
+        storage.save(&Deck((0..100).collect())).unwrap();
+        let mut buffer = String::new();
+        let mut deck: Deck = storage.load(&mut buffer).unwrap().unwrap();
+
+        let changes = storage.connection.total_changes();
+        storage.save(&deck).unwrap();
+        assert_eq!(storage.connection.total_changes(), changes);
+
+        deck.0[0] = 100;
+        storage.save(&deck).unwrap();
+        assert_eq!(storage.connection.total_changes(), changes + 2);
+        let mut buffer = String::new();
+        assert_eq!(
+            storage.load(&mut buffer).unwrap(),
+            Some(Deck((1..=100).collect()))
+        );
+    }
+
+    #[test]
+    fn failed_review_saves_neither_task_nor_log() {
+        let mut storage = Sqlite::open_in_memory().unwrap();
+        storage.save(&Deck(vec![1])).unwrap();
+        storage
+            .connection
+            .execute_batch(
+                "CREATE TRIGGER full_log BEFORE INSERT ON review_log
+                 BEGIN SELECT RAISE(ABORT, 'disk is full'); END;",
+            )
+            .unwrap();
+
+        let entry = ReviewLogEntry {
+            task_id: 2,
+            review: Review {
+                timestamp: SystemTime::now(),
+                grade: Grade::Good,
+                recalled: None,
+                elapsed: Duration::ZERO,
+                response: Vec::new(),
+                interval: Duration::ZERO,
+                state_before: serde_json::Value::Null,
+                state_after: serde_json::Value::Null,
+            },
+        };
+        assert!(storage.save_review(&Deck(vec![1, 2]), &entry).is_err());
+        let mut buffer = String::new();
+        assert_eq!(storage.load(&mut buffer).unwrap(), Some(Deck(vec![1])));
+        assert!(Storage::<Deck>::reviews(&mut storage).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,23 @@
+//! Tags of tasks. Tag can be hierarchical: its levels are separated by [`SEPARATOR`], so
+//! `rust::lifetimes` is nested in `rust`.
+
+/// Separator of tag levels.
+pub const SEPARATOR: &str = "::";
+
+/// Whether `tag` is `category` itself or is nested in it.
+///
+/// # Examples
+///
+/// ```
+/// use ssr_core::tags::is_in;
+///
+/// assert!(is_in("rust::lifetimes", "rust"));
+/// assert!(is_in("rust::lifetimes", "rust::lifetimes"));
+/// assert!(!is_in("rust", "rust::lifetimes"));
+/// assert!(!is_in("rustc", "rust"));
+/// ```
+#[must_use]
+pub fn is_in(tag: &str, category: &str) -> bool {
+    tag.strip_prefix(category)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(SEPARATOR))
+}
@@ -2,22 +2,55 @@ use std::{error::Error, time::SystemTime};
 
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
-use crate::BlocksDatabaseId;
+use crate::{BlocksDatabaseId, review_log::Review};
 
 pub mod level;
 
-pub trait Task<'a>: Serialize + Deserialize<'a> {
+/// Algorithm independent rating of review, used to move progress between algorithms.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+impl Grade {
+    #[must_use]
+    pub fn is_correct(self) -> bool {
+        self != Self::Again
+    }
+}
+
+/// Review of task as it's kept in its history, see [`Task::history`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PastReview {
+    pub timestamp: SystemTime,
+    pub grade: Grade,
+}
+
+/// `Clone` is used to snapshot task before review, so it can be undone.
+pub trait Task<'a>: Serialize + Deserialize<'a> + Clone {
     type SharedState: SharedState<'a>;
 
     /// blocks must contain interactive elements
     fn new(input: s_text_input_f::BlocksWithAnswer) -> Self;
     fn get_blocks(&self) -> s_text_input_f::BlocksWithAnswer;
+    /// Replaces question and answer, scheduling state is kept.
+    /// `input` must contain interactive elements.
+    fn update_blocks(&mut self, input: s_text_input_f::BlocksWithAnswer);
 
     fn next_repetition(
         &self,
         shared_state: &Self::SharedState,
         desired_retention: f64,
     ) -> SystemTime;
+    /// Whether task was never completed.
+    fn is_new(&self) -> bool;
+    /// Probability to recall task at `now`.
+    /// `None` for new tasks or if algorithm doesn't estimate it.
+    fn retrievability(&self, _shared_state: &Self::SharedState, _now: SystemTime) -> Option<f64> {
+        None
+    }
     /// If an error occurs, the task will remain unmodified.
     /// # Errors
     /// If interaction return error.
@@ -28,7 +61,14 @@ pub trait Task<'a>: Serialize + Deserialize<'a> {
         interaction: &mut impl FnMut(
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> std::io::Result<()>;
+    ) -> std::io::Result<Review>;
+
+    /// Reviews, that scheduling state results from, oldest first.
+    /// Empty if task was never completed or algorithm doesn't know its reviews.
+    fn history(&self) -> Vec<PastReview>;
+    /// Applies `reviews`, oldest first, as if they were completed with this algorithm.
+    /// Used on task without reviews, so progress made with other algorithm is kept.
+    fn replay(&mut self, shared_state: &mut Self::SharedState, reviews: &[PastReview]);
 }
 
 pub trait StatelessTask: Serialize + DeserializeOwned {
@@ -51,11 +91,11 @@ pub trait StatelessTask: Serialize + DeserializeOwned {
         interaction: &mut impl FnMut(
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> std::io::Result<()>;
+    ) -> std::io::Result<Review>;
     fn get_id(&self) -> BlocksDatabaseId;
 }
 
-pub trait SharedState<'a>: Default + Serialize + Deserialize<'a> {}
+pub trait SharedState<'a>: Default + Serialize + Deserialize<'a> + Clone {}
 impl SharedState<'_> for () {}
 
 pub trait SharedStateExt<'a, T: Task<'a>>: SharedState<'a> {
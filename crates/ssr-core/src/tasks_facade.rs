@@ -3,7 +3,11 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::task::{SharedStateExt, Task};
+use crate::{
+    review_log::ReviewLogEntry,
+    tags,
+    task::{SharedStateExt, Task},
+};
 
 pub type TaskId = u128;
 
@@ -22,11 +26,13 @@ pub enum Error {
 pub trait TasksFacade<'a, T: Task<'a>>: Serialize + Deserialize<'a> {
     fn new(name: String, desired_retention: f64) -> Self;
     fn get_name(&self) -> &str;
+    fn set_name(&mut self, name: String);
     fn get_desired_retention(&self) -> f64;
     fn set_desired_retention(&mut self, desired_retention: f64);
     fn tasks_total(&self) -> usize;
     fn tasks_to_complete(&self) -> usize;
 
+    /// Returns review log entry of completed task.
     /// If an error occurs, the tasks facade will remain unmodified.
     /// # Errors
     /// If interaction return error.
@@ -36,9 +42,40 @@ pub trait TasksFacade<'a, T: Task<'a>>: Serialize + Deserialize<'a> {
             TaskId,
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> Result<(), Error>;
-    fn insert(&mut self, task: T);
-    fn create_task(&mut self, input: s_text_input_f::BlocksWithAnswer);
+    ) -> Result<ReviewLogEntry, Error>;
+    /// Reverts the latest review completed by this facade: restores task and shared state
+    /// to their state before review and returns task to tasks to complete.
+    /// Can be repeated to undo earlier reviews.
+    /// Returns id of restored task or `None` if there is nothing to undo.
+    fn undo(&mut self) -> Option<TaskId>;
+    /// Returns id of inserted task.
+    fn insert(&mut self, task: T) -> TaskId;
+    /// Returns id of created task.
+    fn create_task(&mut self, input: s_text_input_f::BlocksWithAnswer) -> TaskId;
+    /// Replaces question and answer of task, keeping its progress.
+    /// Returns whether such an element was present.
+    fn update_blocks(&mut self, id: TaskId, input: s_text_input_f::BlocksWithAnswer) -> bool;
+
+    /// Returns tags of task or `None` if there is no such task.
+    fn tags(&self, id: TaskId) -> Option<&[String]>;
+    /// Replaces tags of task.
+    /// Returns whether such an element was present.
+    fn set_tags(&mut self, id: TaskId, tags: Vec<String>) -> bool;
+    /// Returns ids of tasks with any tag in `category`, see [`tags::is_in`].
+    fn tagged(&self, category: &str) -> Vec<TaskId> {
+        self.iter()
+            .map(|(_, id)| id)
+            .filter(|&id| {
+                self.tags(id)
+                    .is_some_and(|tags| tags.iter().any(|tag| tags::is_in(tag, category)))
+            })
+            .collect()
+    }
+    /// Restricts review session to tasks with any tag in `category`: only they are counted by
+    /// [`tasks_to_complete`](Self::tasks_to_complete) and completed by
+    /// [`complete_task`](Self::complete_task). `None` removes restriction.
+    /// Restriction isn't serialized.
+    fn set_session_filter(&mut self, category: Option<String>);
 
     /// Return itrator of (&task, id)
     fn iter<'t>(&'t self) -> impl Iterator<Item = (&'t T, TaskId)>
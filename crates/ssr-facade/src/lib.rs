@@ -1,6 +1,8 @@
 #![warn(clippy::pedantic)]
 #![feature(iter_collect_into)]
 
+pub mod limits;
+pub mod order;
 pub mod stateful;
 
 pub mod stateless;
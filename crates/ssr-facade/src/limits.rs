@@ -0,0 +1,117 @@
+//! Daily limits of completed tasks, so bulk import doesn't flood a single day.
+
+use std::time::SystemTime;
+
+use chrono::{DateTime, Days, Local, NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+/// Caps of tasks completed per day.
+///
+/// # Examples
+///
+/// ```
+/// use s_text_input_f::{Block, BlocksWithAnswer, ParagraphItem};
+/// use ssr_algorithms::leitner_system::WriteAnswer;
+/// use ssr_core::tasks_facade::TasksFacade;
+/// use ssr_facade::{limits::DailyLimits, stateful::Facade};
+///
+/// let mut facade = Facade::<WriteAnswer>::new("bulk import".to_owned(), 0.9);
+/// for _ in 0..10 {
+///     facade.create_task(BlocksWithAnswer {
+///         blocks: vec![Block::Paragraph(vec![ParagraphItem::Placeholder])],
+///         answer: vec![vec!["answer".to_owned()]],
+///     });
+/// }
+/// facade.find_tasks_to_recall();
+/// facade.set_daily_limits(DailyLimits {
+///     new: Some(2),
+///     ..DailyLimits::default()
+/// });
+/// assert_eq!(facade.tasks_to_complete(), 2);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyLimits {
+    /// New tasks introduced per day.
+    pub new: Option<usize>,
+    /// All tasks completed per day, new ones included.
+    pub reviews: Option<usize>,
+    /// Hour of local time, when the next day starts. So tasks completed after midnight, but
+    /// before this hour, count to the previous day.
+    pub day_starts_at: u32,
+}
+
+impl Default for DailyLimits {
+    fn default() -> Self {
+        Self {
+            new: None,
+            reviews: None,
+            day_starts_at: 4,
+        }
+    }
+}
+
+impl DailyLimits {
+    /// Day, that `time` belongs to.
+    #[must_use]
+    pub fn day(&self, time: SystemTime) -> NaiveDate {
+        (DateTime::<Local>::from(time) - TimeDelta::hours(self.day_starts_at.into())).date_naive()
+    }
+    /// Start of the day after the one `time` belongs to.
+    #[must_use]
+    pub fn next_day_start(&self, time: SystemTime) -> SystemTime {
+        (self.day(time) + Days::new(1))
+            .and_hms_opt(self.day_starts_at, 0, 0)
+            .and_then(|start| start.and_local_timezone(Local).earliest())
+            .map_or(time, SystemTime::from)
+    }
+
+    pub(crate) fn remaining(&self, counts: &DailyCounts, now: SystemTime) -> Remaining {
+        let counts = counts.of(self.day(now));
+        Remaining {
+            new: self
+                .new
+                .map_or(usize::MAX, |max| max.saturating_sub(counts.new)),
+            reviews: self
+                .reviews
+                .map_or(usize::MAX, |max| max.saturating_sub(counts.reviews)),
+        }
+    }
+}
+
+/// Tasks completed during one day.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DailyCounts {
+    /// `None` if nothing is completed yet.
+    pub day: Option<NaiveDate>,
+    pub new: usize,
+    /// All completed tasks, new ones included.
+    pub reviews: usize,
+}
+
+impl DailyCounts {
+    /// Counts of `day`: zeros, if these are counts of another day.
+    #[must_use]
+    pub fn of(&self, day: NaiveDate) -> Self {
+        if self.day == Some(day) {
+            *self
+        } else {
+            Self {
+                day: Some(day),
+                ..Self::default()
+            }
+        }
+    }
+}
+
+/// How many tasks can still be completed today.
+#[derive(Clone, Copy)]
+pub(crate) struct Remaining {
+    pub(crate) new: usize,
+    pub(crate) reviews: usize,
+}
+
+impl Remaining {
+    pub(crate) fn allows(self, is_new: bool) -> bool {
+        self.reviews > 0 && (!is_new || self.new > 0)
+    }
+}
@@ -0,0 +1,60 @@
+//! Strategies of choosing the next task to complete.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// How [`Facade`](crate::stateful::Facade) chooses the next task among tasks to complete.
+///
+/// # Examples
+///
+/// ```
+/// use ssr_algorithms::leitner_system::WriteAnswer;
+/// use ssr_core::tasks_facade::TasksFacade;
+/// use ssr_facade::{order::Order, stateful::Facade};
+///
+/// let mut facade = Facade::<WriteAnswer>::new("deck".to_owned(), 0.9);
+/// assert_eq!(facade.order(), Order::Random);
+/// facade.set_order(Order::MostOverdue);
+/// facade.set_seed(7);
+/// assert_eq!(facade.order(), Order::MostOverdue);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    /// Uniformly random. Random generator can be seeded with
+    /// [`Facade::set_seed`](crate::stateful::Facade::set_seed).
+    #[default]
+    Random,
+    /// Task, that became due the earliest.
+    MostOverdue,
+    /// Task with the lowest probability to be recalled now. Tasks, which retrievability is
+    /// unknown (e.g. new ones), are the last, the most overdue first.
+    LowestRetrievability,
+    /// Reviews and new tasks in turn, while there are both. Random among each kind.
+    NewInterleaved,
+    /// Top level tag categories in turn, in alphabetical order. Untagged tasks form their own
+    /// category, task with several tags belongs to category of the first one. Random inside
+    /// category.
+    TagRoundRobin,
+}
+
+/// Session state of [`Order`].
+#[derive(Debug)]
+pub(crate) struct Picker {
+    /// Unlike `StdRng`, gives the same sequence in every version of `rand`, so seeded session
+    /// doesn't change with dependencies.
+    pub(crate) rng: ChaCha8Rng,
+    /// Kind of the latest task for [`Order::NewInterleaved`].
+    pub(crate) last_was_new: Option<bool>,
+    /// Category of the latest task for [`Order::TagRoundRobin`].
+    pub(crate) last_category: Option<String>,
+}
+
+impl Default for Picker {
+    fn default() -> Self {
+        Self {
+            rng: ChaCha8Rng::from_os_rng(),
+            last_was_new: None,
+            last_category: None,
+        }
+    }
+}
@@ -1,12 +1,21 @@
 use std::time::{Duration, SystemTime};
 
-use rand::Rng;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned};
 use ssr_core::{
+    review_log::ReviewLogEntry,
+    storage::StoredFacade,
+    tags,
     task::{SharedStateExt, Task},
     tasks_facade::{TaskId, TasksFacade},
 };
 
+use crate::{
+    limits::{DailyCounts, DailyLimits},
+    order::{Order, Picker},
+};
+
 pub(crate) fn serialize_id<S>(id: &TaskId, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -25,12 +34,16 @@ where
     Ok(id)
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(bound(deserialize = "T: Task<'de>"))]
-pub(crate) struct TaskWrapper<T> {
+pub struct TaskWrapper<T> {
     pub(crate) task: T,
     #[serde(serialize_with = "serialize_id", deserialize_with = "deserialize_id")]
     pub(crate) id: TaskId,
+    #[serde(default, skip_serializing_if = "Flags::is_empty")]
+    pub(crate) flags: Flags,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<String>,
 }
 
 impl<'a, T: Task<'a>> TaskWrapper<T> {
@@ -38,8 +51,62 @@ impl<'a, T: Task<'a>> TaskWrapper<T> {
         Self {
             task: value,
             id: rand::random(),
+            flags: Flags::default(),
+            tags: Vec::new(),
         }
     }
+
+    /// Top level category of the first tag, empty for untagged task.
+    fn category(&self) -> &str {
+        self.tags
+            .first()
+            .and_then(|tag| tag.split(tags::SEPARATOR).next())
+            .unwrap_or_default()
+    }
+
+    fn is_in(&self, category: Option<&str>) -> bool {
+        category.is_none_or(|category| self.tags.iter().any(|tag| tags::is_in(tag, category)))
+    }
+
+    /// `None` if task is suspended.
+    fn due(&self, state: &T::SharedState, desired_retention: f64) -> Option<SystemTime> {
+        self.flags
+            .apply(self.task.next_repetition(state, desired_retention))
+    }
+}
+
+/// Manual changes of task schedule.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Flags {
+    /// Suspended task is never due.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suspended: bool,
+    /// Task isn't due before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buried_until: Option<SystemTime>,
+    /// Due time instead of the one chosen by algorithm, until the next review.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rescheduled_to: Option<SystemTime>,
+}
+
+impl Flags {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Due time of task, which algorithm schedules to `next_repetition`.
+    /// `None` if task is suspended.
+    #[must_use]
+    pub fn apply(&self, next_repetition: SystemTime) -> Option<SystemTime> {
+        if self.suspended {
+            return None;
+        }
+        let due = self.rescheduled_to.unwrap_or(next_repetition);
+        Some(
+            self.buried_until
+                .map_or(due, |buried_until| due.max(buried_until)),
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -53,6 +120,29 @@ where
     pub(crate) tasks_to_recall: Vec<TaskWrapper<T>>,
     pub(crate) desired_retention: f64,
     pub(crate) state: T::SharedState,
+    #[serde(default)]
+    pub(crate) daily_limits: DailyLimits,
+    /// Tasks completed during the latest day with reviews.
+    #[serde(default)]
+    pub(crate) daily_counts: DailyCounts,
+    /// Snapshots before reviews of this session, the latest is last.
+    #[serde(skip)]
+    pub(crate) history: Vec<Snapshot<'a, T>>,
+    /// Category of tags, that review session is restricted to.
+    #[serde(skip)]
+    pub(crate) session_filter: Option<String>,
+    #[serde(skip)]
+    pub(crate) order: Order,
+    #[serde(skip)]
+    pub(crate) picker: Picker,
+}
+
+/// Task, shared state and daily counts before review.
+#[derive(Debug)]
+pub(crate) struct Snapshot<'a, T: Task<'a>> {
+    task: TaskWrapper<T>,
+    state: T::SharedState,
+    daily_counts: DailyCounts,
 }
 
 impl<'a, T: Task<'a>> Facade<'a, T> {
@@ -60,7 +150,8 @@ impl<'a, T: Task<'a>> Facade<'a, T> {
         let now = SystemTime::now() + Duration::from_secs(10);
         self.tasks_pool
             .extract_if(.., |t| {
-                t.task.next_repetition(&self.state, self.desired_retention) <= now
+                t.due(&self.state, self.desired_retention)
+                    .is_some_and(|due| due <= now)
             })
             .collect_into(&mut self.tasks_to_recall);
     }
@@ -71,53 +162,320 @@ impl<'a, T: Task<'a>> Facade<'a, T> {
         self.find_tasks_to_recall();
     }
 
-    pub(crate) fn take_random_task(&mut self) -> Option<TaskWrapper<T>> {
-        if self.tasks_to_recall.is_empty() {
+    /// Removes task from both queues.
+    pub(crate) fn take(&mut self, id: TaskId) -> Option<TaskWrapper<T>> {
+        if let Some(index) = self.tasks_to_recall.iter().position(|x| x.id == id) {
+            return Some(self.tasks_to_recall.swap_remove(index));
+        }
+        let index = self.tasks_pool.iter().position(|x| x.id == id)?;
+        Some(self.tasks_pool.swap_remove(index))
+    }
+    /// Takes task to recall, that passes session filter and daily limits, according to order.
+    pub(crate) fn take_next_task(&mut self) -> Option<TaskWrapper<T>> {
+        let filter = self.session_filter.as_deref();
+        let remaining = self
+            .daily_limits
+            .remaining(&self.daily_counts, SystemTime::now());
+        let mut candidates = self
+            .tasks_to_recall
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.is_in(filter) && remaining.allows(t.task.is_new()))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
             return None;
         }
-        let index = rand::rng().random_range(0..self.tasks_to_recall.len());
+        let tasks = &self.tasks_to_recall;
+        let due = |i: usize| tasks[i].due(&self.state, self.desired_retention);
+        let index = match self.order {
+            Order::Random => candidates[self.picker.rng.random_range(0..candidates.len())],
+            Order::MostOverdue => candidates
+                .into_iter()
+                .min_by_key(|&i| (due(i), tasks[i].id))
+                .unwrap(),
+            Order::LowestRetrievability => {
+                let now = SystemTime::now();
+                let retrievability = |i: usize| tasks[i].task.retrievability(&self.state, now);
+                candidates
+                    .into_iter()
+                    .map(|i| (i, retrievability(i)))
+                    .min_by(|(a, a_retrievability), (b, b_retrievability)| {
+                        match (a_retrievability, b_retrievability) {
+                            (Some(a), Some(b)) => a.total_cmp(b),
+                            (Some(_), None) => std::cmp::Ordering::Less,
+                            (None, Some(_)) => std::cmp::Ordering::Greater,
+                            (None, None) => std::cmp::Ordering::Equal,
+                        }
+                        .then_with(|| due(*a).cmp(&due(*b)))
+                        .then_with(|| tasks[*a].id.cmp(&tasks[*b].id))
+                    })
+                    .unwrap()
+                    .0
+            }
+            Order::NewInterleaved => {
+                let want_new = self.picker.last_was_new == Some(false);
+                if candidates
+                    .iter()
+                    .any(|&i| tasks[i].task.is_new() == want_new)
+                {
+                    candidates.retain(|&i| tasks[i].task.is_new() == want_new);
+                }
+                let index = candidates[self.picker.rng.random_range(0..candidates.len())];
+                self.picker.last_was_new = Some(tasks[index].task.is_new());
+                index
+            }
+            Order::TagRoundRobin => {
+                let mut categories = candidates
+                    .iter()
+                    .map(|&i| tasks[i].category())
+                    .collect::<Vec<_>>();
+                categories.sort_unstable();
+                categories.dedup();
+                let category = match &self.picker.last_category {
+                    Some(last) => categories
+                        .iter()
+                        .find(|&&x| x > last.as_str())
+                        .unwrap_or(&categories[0]),
+                    None => &categories[0],
+                };
+                candidates.retain(|&i| tasks[i].category() == *category);
+                self.picker.last_category = Some((*category).to_owned());
+                candidates[self.picker.rng.random_range(0..candidates.len())]
+            }
+        };
         Some(self.tasks_to_recall.swap_remove(index))
     }
 
+    pub fn order(&self) -> Order {
+        self.order
+    }
+    /// Sets order of the following tasks in this session. Order isn't serialized.
+    pub fn set_order(&mut self, order: Order) {
+        self.order = order;
+        self.picker.last_was_new = None;
+        self.picker.last_category = None;
+    }
+    /// Seeds random generator, so the same tasks are completed in the same order.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.picker.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    pub fn shared_state(&self) -> &T::SharedState {
+        &self.state
+    }
+    /// Replaces algorithm state, e.g. its settings. Clears undo history like `optimize`.
+    pub fn set_shared_state(&mut self, state: T::SharedState) {
+        self.state = state;
+        self.history.clear();
+
+        self.reload_all_tasks_timings();
+    }
+    /// Replaces each task with result of `f`, e.g. to move it to another algorithm.
+    /// Clears undo history.
+    pub fn replace_tasks(&mut self, mut f: impl FnMut(&T, &mut T::SharedState) -> T) {
+        for wrapper in self
+            .tasks_pool
+            .iter_mut()
+            .chain(self.tasks_to_recall.iter_mut())
+        {
+            wrapper.task = f(&wrapper.task, &mut self.state);
+        }
+        self.history.clear();
+
+        self.reload_all_tasks_timings();
+    }
+    /// Time until the next task, that passes session filter, must be completed.
+    /// Tasks over daily limits wait for the next day.
+    /// `None` if there are no such tasks or all of them are suspended.
     pub fn until_next_repetition(&self) -> Option<Duration> {
-        if self.tasks_total() == 0 {
-            None
-        } else if self.tasks_to_complete() > 0 {
-            Some(Duration::default())
-        } else {
-            self.tasks_pool
-                .iter()
-                .map(|t| {
-                    t.task
-                        .next_repetition(&self.state, self.desired_retention)
-                        .duration_since(SystemTime::now())
-                        .unwrap_or(Duration::default())
-                })
-                .min()
+        if self.tasks_to_complete() > 0 {
+            return Some(Duration::default());
         }
+        let now = SystemTime::now();
+        let remaining = self.daily_limits.remaining(&self.daily_counts, now);
+        let next_day = self.daily_limits.next_day_start(now);
+        self.tasks_pool
+            .iter()
+            .chain(self.tasks_to_recall.iter())
+            .filter(|t| t.is_in(self.session_filter.as_deref()))
+            .filter_map(|t| {
+                let due = t.due(&self.state, self.desired_retention)?;
+                Some(if remaining.allows(t.task.is_new()) {
+                    due
+                } else {
+                    due.max(next_day)
+                })
+            })
+            .map(|due| due.duration_since(now).unwrap_or(Duration::default()))
+            .min()
+    }
+
+    pub fn daily_limits(&self) -> DailyLimits {
+        self.daily_limits
+    }
+    pub fn set_daily_limits(&mut self, limits: DailyLimits) {
+        self.daily_limits = limits;
+    }
+    /// Tasks completed today.
+    pub fn daily_counts(&self) -> DailyCounts {
+        self.daily_counts
+            .of(self.daily_limits.day(SystemTime::now()))
+    }
+
+    pub fn iter_with_flags(&self) -> impl Iterator<Item = (&T, TaskId, &Flags)> {
+        self.tasks_pool
+            .iter()
+            .chain(self.tasks_to_recall.iter())
+            .map(|t| (&t.task, t.id, &t.flags))
+    }
+
+    /// Changes task and moves it to the right queue. Returns `false` if there is no such task.
+    fn modify(&mut self, id: TaskId, f: impl FnOnce(&mut TaskWrapper<T>)) -> bool {
+        let Some(mut task) = self.take(id) else {
+            return false;
+        };
+        f(&mut task);
+        self.tasks_pool.push(task);
+        self.find_tasks_to_recall();
+        true
+    }
+
+    /// Suspended task is never due, until unsuspended.
+    pub fn set_suspended(&mut self, id: TaskId, suspended: bool) -> bool {
+        self.modify(id, |t| t.flags.suspended = suspended)
+    }
+    /// Task isn't due before `until`. `None` unburies it.
+    pub fn bury(&mut self, id: TaskId, until: Option<SystemTime>) -> bool {
+        self.modify(id, |t| t.flags.buried_until = until)
+    }
+    /// Task is due at `due` instead of the time chosen by algorithm. The next review clears it.
+    pub fn reschedule(&mut self, id: TaskId, due: SystemTime) -> bool {
+        self.modify(id, |t| {
+            t.flags.rescheduled_to = Some(due);
+            t.flags.buried_until = None;
+        })
+    }
+    /// Forgets progress of task, so it becomes new. Task stays suspended, if it was.
+    pub fn reset(&mut self, id: TaskId) -> bool {
+        // Undo must not bring progress back.
+        self.history.retain(|snapshot| snapshot.task.id != id);
+        self.modify(id, |t| {
+            t.task = T::new(t.task.get_blocks());
+            t.flags = Flags {
+                suspended: t.flags.suspended,
+                ..Flags::default()
+            };
+        })
     }
 }
 
 impl<'a, F: Task<'a>> Facade<'a, F> {
+    /// Same facade with tasks of another algorithm, that uses `state`. Review history of each
+    /// task is replayed into it, so scheduling continues. Ids, flags, tags and daily counts are
+    /// kept, so review log still refers to the same tasks.
     /// # Warning
-    /// You will loose all progress.
-    pub fn migrate<T: Task<'a> + std::fmt::Debug>(&self) -> Facade<'a, T>
+    /// Progress of tasks, whose algorithm doesn't know their reviews, is lost.
+    pub fn migrate<T: Task<'a> + std::fmt::Debug>(&self, mut state: T::SharedState) -> Facade<'a, T>
     where
         T::SharedState: std::fmt::Debug,
     {
-        let task_templates = self
+        let tasks_pool = self
             .tasks_pool
             .iter()
             .chain(self.tasks_to_recall.iter())
-            .map(|t| t.task.get_blocks());
+            .map(|t| {
+                let mut task = T::new(t.task.get_blocks());
+                task.replay(&mut state, &t.task.history());
+                TaskWrapper {
+                    task,
+                    id: t.id,
+                    flags: t.flags.clone(),
+                    tags: t.tags.clone(),
+                }
+            })
+            .collect();
         let mut new_facade = Facade::new(self.name.clone(), self.desired_retention);
-        for i in task_templates {
-            new_facade.create_task(i);
-        }
+        new_facade.tasks_pool = tasks_pool;
+        new_facade.state = state;
+        new_facade.daily_limits = self.daily_limits;
+        new_facade.daily_counts = self.daily_counts;
+        new_facade.find_tasks_to_recall();
         new_facade
     }
 }
 
+/// Everything in [`Facade`] except tasks.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FacadeHeader<S> {
+    name: String,
+    desired_retention: f64,
+    state: S,
+    #[serde(default)]
+    daily_limits: DailyLimits,
+    #[serde(default)]
+    daily_counts: DailyCounts,
+}
+
+impl<'a, T> StoredFacade for Facade<'a, T>
+where
+    T: Task<'a>,
+    T::SharedState: Clone + DeserializeOwned,
+    TaskWrapper<T>: DeserializeOwned,
+{
+    type Header = FacadeHeader<T::SharedState>;
+    type Record = TaskWrapper<T>;
+
+    fn header(&self) -> Self::Header {
+        FacadeHeader {
+            name: self.name.clone(),
+            desired_retention: self.desired_retention,
+            state: self.state.clone(),
+            daily_limits: self.daily_limits,
+            daily_counts: self.daily_counts,
+        }
+    }
+
+    fn record(&self, id: TaskId) -> Option<&Self::Record> {
+        self.tasks_pool
+            .iter()
+            .chain(self.tasks_to_recall.iter())
+            .find(|t| t.id == id)
+    }
+
+    fn records(&self) -> impl Iterator<Item = (TaskId, &Self::Record)> {
+        self.tasks_pool
+            .iter()
+            .chain(self.tasks_to_recall.iter())
+            .map(|t| (t.id, t))
+    }
+
+    fn from_parts(header: Self::Header, records: Vec<Self::Record>) -> Self {
+        let FacadeHeader {
+            name,
+            desired_retention,
+            state,
+            daily_limits,
+            daily_counts,
+        } = header;
+        let mut facade = Self {
+            name,
+            tasks_pool: records,
+            tasks_to_recall: Vec::default(),
+            desired_retention,
+            state,
+            daily_limits,
+            daily_counts,
+            history: Vec::new(),
+            session_filter: None,
+            order: Order::default(),
+            picker: Picker::default(),
+        };
+        facade.find_tasks_to_recall();
+        facade
+    }
+}
+
 impl<'a, T: Task<'a>> TasksFacade<'a, T> for Facade<'a, T> {
     fn new(name: String, desired_retention: f64) -> Self {
         Self {
@@ -126,18 +484,36 @@ impl<'a, T: Task<'a>> TasksFacade<'a, T> for Facade<'a, T> {
             tasks_to_recall: Vec::default(),
             desired_retention,
             state: T::SharedState::default(),
+            daily_limits: DailyLimits::default(),
+            daily_counts: DailyCounts::default(),
+            history: Vec::new(),
+            session_filter: None,
+            order: Order::default(),
+            picker: Picker::default(),
         }
     }
 
     fn get_name(&self) -> &str {
         &self.name
     }
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
 
     fn tasks_total(&self) -> usize {
         self.tasks_pool.len() + self.tasks_to_recall.len()
     }
     fn tasks_to_complete(&self) -> usize {
-        self.tasks_to_recall.len()
+        let filter = self.session_filter.as_deref();
+        let remaining = self
+            .daily_limits
+            .remaining(&self.daily_counts, SystemTime::now());
+        let (new, reviews): (Vec<_>, Vec<_>) = self
+            .tasks_to_recall
+            .iter()
+            .filter(|t| t.is_in(filter))
+            .partition(|t| t.task.is_new());
+        (reviews.len() + new.len().min(remaining.new)).min(remaining.reviews)
     }
 
     fn complete_task(
@@ -146,9 +522,15 @@ impl<'a, T: Task<'a>> TasksFacade<'a, T> for Facade<'a, T> {
             TaskId,
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> Result<(), ssr_core::tasks_facade::Error> {
+    ) -> Result<ReviewLogEntry, ssr_core::tasks_facade::Error> {
         self.find_tasks_to_recall();
-        let Some(TaskWrapper { mut task, id }) = self.take_random_task() else {
+        let Some(TaskWrapper {
+            mut task,
+            id,
+            mut flags,
+            tags,
+        }) = self.take_next_task()
+        else {
             return match self.until_next_repetition() {
                 Some(time_until_next_repetition) => {
                     Err(ssr_core::tasks_facade::Error::NoTaskToComplete {
@@ -158,19 +540,48 @@ impl<'a, T: Task<'a>> TasksFacade<'a, T> for Facade<'a, T> {
                 None => Err(ssr_core::tasks_facade::Error::NoTask),
             };
         };
-        let err = task.complete(&mut self.state, self.desired_retention, &mut |blocks| {
+        let snapshot = Snapshot {
+            task: TaskWrapper {
+                task: task.clone(),
+                id,
+                flags: flags.clone(),
+                tags: tags.clone(),
+            },
+            state: self.state.clone(),
+            daily_counts: self.daily_counts,
+        };
+        let was_new = task.is_new();
+        let result = task.complete(&mut self.state, self.desired_retention, &mut |blocks| {
             interaction(id, blocks)
         });
-        if let Err(err) = err {
-            self.tasks_pool.push(TaskWrapper { task, id });
-            return Err(err.into());
+        if result.is_ok() {
+            flags.rescheduled_to = None;
         }
-        self.tasks_pool.push(TaskWrapper { task, id });
-        Ok(())
+        self.tasks_pool.push(TaskWrapper {
+            task,
+            id,
+            flags,
+            tags,
+        });
+        let review = result?;
+        self.history.push(snapshot);
+        let mut counts = self
+            .daily_counts
+            .of(self.daily_limits.day(review.timestamp));
+        counts.reviews += 1;
+        counts.new += usize::from(was_new);
+        self.daily_counts = counts;
+        Ok(ReviewLogEntry {
+            task_id: id,
+            review,
+        })
     }
 
-    fn insert(&mut self, task: T) {
-        self.tasks_pool.push(TaskWrapper::new(task));
+    fn insert(&mut self, task: T) -> TaskId {
+        let task = TaskWrapper::new(task);
+        let id = task.id;
+        self.tasks_pool.push(task);
+        id
     }
 
     fn iter<'t>(&'t self) -> impl Iterator<Item = (&'t T, TaskId)>
@@ -180,30 +591,75 @@ impl<'a, T: Task<'a>> TasksFacade<'a, T> for Facade<'a, T> {
         self.tasks_pool
             .iter()
             .chain(self.tasks_to_recall.iter())
-            .map(|TaskWrapper { task, id }| (task, *id))
+            .map(|TaskWrapper { task, id, .. }| (task, *id))
     }
 
-    fn remove(&mut self, id: TaskId) -> bool {
-        let mut removed = false;
-        self.tasks_to_recall.retain(|task_wrapper| {
-            if task_wrapper.id == id {
-                removed = true;
-                false
-            } else {
-                true
+    fn update_blocks(&mut self, id: TaskId, input: s_text_input_f::BlocksWithAnswer) -> bool {
+        // Undo must not bring old content back.
+        for snapshot in &mut self.history {
+            if snapshot.task.id == id {
+                snapshot.task.task.update_blocks(input.clone());
+            }
+        }
+        let Some(task) = self
+            .tasks_pool
+            .iter_mut()
+            .chain(self.tasks_to_recall.iter_mut())
+            .find(|x| x.id == id)
+        else {
+            return false;
+        };
+        task.task.update_blocks(input);
+        true
+    }
+
+    fn tags(&self, id: TaskId) -> Option<&[String]> {
+        self.tasks_pool
+            .iter()
+            .chain(self.tasks_to_recall.iter())
+            .find(|t| t.id == id)
+            .map(|t| t.tags.as_slice())
+    }
+
+    fn set_tags(&mut self, id: TaskId, tags: Vec<String>) -> bool {
+        for snapshot in &mut self.history {
+            if snapshot.task.id == id {
+                snapshot.task.tags.clone_from(&tags);
             }
-        });
-        if !removed {
-            self.tasks_pool.retain(|task_wrapper| {
-                if task_wrapper.id == id {
-                    removed = true;
-                    false
-                } else {
-                    true
-                }
-            });
         }
-        removed
+        let Some(task) = self
+            .tasks_pool
+            .iter_mut()
+            .chain(self.tasks_to_recall.iter_mut())
+            .find(|x| x.id == id)
+        else {
+            return false;
+        };
+        task.tags = tags;
+        true
+    }
+
+    fn set_session_filter(&mut self, category: Option<String>) {
+        self.session_filter = category;
+    }
+
+    fn remove(&mut self, id: TaskId) -> bool {
+        self.history.retain(|snapshot| snapshot.task.id != id);
+        self.take(id).is_some()
+    }
+
+    fn undo(&mut self) -> Option<TaskId> {
+        let Snapshot {
+            task,
+            state,
+            daily_counts,
+        } = self.history.pop()?;
+        let id = task.id;
+        self.take(id);
+        self.tasks_to_recall.push(task);
+        self.state = state;
+        self.daily_counts = daily_counts;
+        Some(id)
     }
 
     fn get_desired_retention(&self) -> f64 {
@@ -216,8 +672,8 @@ impl<'a, T: Task<'a>> TasksFacade<'a, T> for Facade<'a, T> {
         self.reload_all_tasks_timings();
     }
 
-    fn create_task(&mut self, input: s_text_input_f::BlocksWithAnswer) {
-        self.insert(T::new(input));
+    fn create_task(&mut self, input: s_text_input_f::BlocksWithAnswer) -> TaskId {
+        self.insert(T::new(input))
     }
 
     fn optimize(&mut self) -> Result<(), Box<dyn std::error::Error>>
@@ -230,6 +686,8 @@ impl<'a, T: Task<'a>> TasksFacade<'a, T> for Facade<'a, T> {
             .chain(self.tasks_to_recall.iter())
             .map(|x| &x.task);
         self.state.optimize(items)?;
+        // Undo would silently revert optimized state.
+        self.history.clear();
 
         self.reload_all_tasks_timings();
         Ok(())
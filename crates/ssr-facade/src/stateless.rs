@@ -1,7 +1,7 @@
 use std::time::{Duration, SystemTime};
 
 use rand::Rng;
-use ssr_core::{BlocksDatabaseId, task::StatelessTask};
+use ssr_core::{BlocksDatabaseId, review_log::ReviewLogEntry, task::StatelessTask};
 
 pub struct Facade<T, U>
 where
@@ -84,7 +84,7 @@ where
         interaction: &mut impl FnMut(
             s_text_input_f::Blocks,
         ) -> std::io::Result<s_text_input_f::Response>,
-    ) -> Result<(), ssr_core::tasks_facade::Error> {
+    ) -> Result<ReviewLogEntry, ssr_core::tasks_facade::Error> {
         self.find_tasks_to_recall();
         let Some(mut task) = self.take_random_task() else {
             return match self.until_next_repetition() {
@@ -96,19 +96,18 @@ where
                 None => Err(ssr_core::tasks_facade::Error::NoTask),
             };
         };
-        let err = task.complete(
+        let result = task.complete(
             check(task.get_id()),
             &mut self.state,
             self.desired_retention,
             interaction,
         );
-        if let Err(err) = err {
-            self.tasks_pool.push(task);
-            return Err(err.into());
-        }
-
+        let task_id = task.get_id().into();
         self.tasks_pool.push(task);
 
-        Ok(())
+        Ok(ReviewLogEntry {
+            task_id,
+            review: result?,
+        })
     }
 }
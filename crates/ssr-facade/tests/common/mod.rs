@@ -0,0 +1,30 @@
+use s_text_input_f::{Block, BlocksWithAnswer, ParagraphItem};
+use ssr_algorithms::leitner_system::WriteAnswer;
+use ssr_core::tasks_facade::{TaskId, TasksFacade};
+use ssr_facade::stateful::Facade;
+
+pub type TestFacade<'a> = Facade<'a, WriteAnswer>;
+
+/// Facade with `count` new tasks, their ids are in order of creation.
+pub fn facade(name: &str, count: usize) -> (TestFacade<'static>, Vec<TaskId>) {
+    let mut facade = Facade::new(name.to_owned(), 0.9);
+    let ids = (0..count)
+        .map(|_| {
+            facade.create_task(BlocksWithAnswer {
+                blocks: vec![Block::Paragraph(vec![ParagraphItem::Placeholder])],
+                answer: vec![vec!["answer".to_owned()]],
+            })
+        })
+        .collect();
+    (facade, ids)
+}
+
+/// Completes tasks while there are any, answering wrong. Returns positions in `ids` of tasks in
+/// order they are asked.
+pub fn session(facade: &mut TestFacade, ids: &[TaskId]) -> Vec<usize> {
+    let mut order = Vec::new();
+    while let Ok(entry) = facade.complete_task(&mut |_, _| Ok(vec![vec![String::new()]])) {
+        order.push(ids.iter().position(|&id| id == entry.task_id).unwrap());
+    }
+    order
+}
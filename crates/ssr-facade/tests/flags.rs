@@ -0,0 +1,74 @@
+use std::time::{Duration, SystemTime};
+
+use common::{TestFacade, facade, session};
+use ssr_core::tasks_facade::TasksFacade;
+
+mod common;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Saves and loads facade, as it's done between runs.
+fn restart<'a>(facade: &TestFacade, json: &'a mut String) -> TestFacade<'a> {
+    *json = serde_json::to_string(facade).unwrap();
+    let mut facade: TestFacade = serde_json::from_str(json).unwrap();
+    facade.find_tasks_to_recall();
+    facade
+}
+
+/// Whether `until` is `expected` up to duration of test.
+fn is_about(until: Option<Duration>, expected: Duration) -> bool {
+    until.is_some_and(|until| until <= expected && expected - until < Duration::from_secs(60))
+}
+
+#[test]
+fn suspended_task_is_excluded_until_unsuspended() {
+    let (mut facade, ids) = facade("suspend", 1);
+    facade.set_suspended(ids[0], true);
+    let mut json = String::new();
+    let mut facade = restart(&facade, &mut json);
+    assert_eq!(facade.tasks_to_complete(), 0);
+    assert_eq!(facade.until_next_repetition(), None);
+
+    facade.set_suspended(ids[0], false);
+    let mut json = String::new();
+    let mut facade = restart(&facade, &mut json);
+    assert_eq!(facade.tasks_to_complete(), 1);
+    assert_eq!(session(&mut facade, &ids), [0]);
+}
+
+#[test]
+fn buried_task_is_excluded_until_next_day() {
+    let (mut facade, ids) = facade("bury", 1);
+    let now = SystemTime::now();
+    let next_day = facade.daily_limits().next_day_start(now);
+    facade.bury(ids[0], Some(next_day));
+    let mut json = String::new();
+    let mut facade = restart(&facade, &mut json);
+    assert_eq!(facade.tasks_to_complete(), 0);
+    let expected = next_day.duration_since(now).unwrap();
+    assert!(is_about(facade.until_next_repetition(), expected));
+
+    // As if the next day came.
+    facade.bury(ids[0], Some(now - HOUR));
+    let mut json = String::new();
+    let mut facade = restart(&facade, &mut json);
+    assert_eq!(facade.tasks_to_complete(), 1);
+    assert_eq!(session(&mut facade, &ids), [0]);
+}
+
+#[test]
+fn rescheduled_task_is_excluded_until_due() {
+    let (mut facade, ids) = facade("reschedule", 1);
+    let now = SystemTime::now();
+    facade.reschedule(ids[0], now + HOUR);
+    let mut json = String::new();
+    let mut facade = restart(&facade, &mut json);
+    assert_eq!(facade.tasks_to_complete(), 0);
+    assert!(is_about(facade.until_next_repetition(), HOUR));
+
+    facade.reschedule(ids[0], now - HOUR);
+    let mut json = String::new();
+    let mut facade = restart(&facade, &mut json);
+    assert_eq!(facade.tasks_to_complete(), 1);
+    assert_eq!(session(&mut facade, &ids), [0]);
+}
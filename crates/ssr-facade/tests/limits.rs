@@ -0,0 +1,61 @@
+use std::time::SystemTime;
+
+use common::{TestFacade, facade, session};
+use ssr_core::tasks_facade::TasksFacade;
+use ssr_facade::limits::DailyLimits;
+
+mod common;
+
+#[test]
+fn new_tasks_are_limited_per_day() {
+    let (mut facade, ids) = facade("bulk import", 300);
+    facade.find_tasks_to_recall();
+    facade.set_daily_limits(DailyLimits {
+        new: Some(2),
+        ..DailyLimits::default()
+    });
+    assert_eq!(facade.tasks_to_complete(), 2);
+
+    assert_eq!(session(&mut facade, &ids).len(), 2);
+    assert_eq!(facade.tasks_to_complete(), 0);
+    assert_eq!(facade.daily_counts().new, 2);
+    assert_eq!(facade.daily_counts().reviews, 2);
+}
+
+#[test]
+fn reviews_limit_includes_new_tasks() {
+    let (mut facade, ids) = facade("reviews", 10);
+    facade.set_daily_limits(DailyLimits {
+        new: Some(5),
+        reviews: Some(3),
+        ..DailyLimits::default()
+    });
+    assert_eq!(session(&mut facade, &ids).len(), 3);
+}
+
+#[test]
+fn counts_survive_restart() {
+    let (mut facade, ids) = facade("restart", 5);
+    facade.set_daily_limits(DailyLimits {
+        new: Some(2),
+        ..DailyLimits::default()
+    });
+    session(&mut facade, &ids);
+
+    let json = serde_json::to_string(&facade).unwrap();
+    let mut facade: TestFacade = serde_json::from_str(&json).unwrap();
+    facade.find_tasks_to_recall();
+    assert_eq!(facade.daily_counts().new, 2);
+    assert_eq!(facade.tasks_to_complete(), 0);
+    // Limited tasks wait for the next day.
+    assert!(facade.until_next_repetition().unwrap().as_secs() > 0);
+}
+
+#[test]
+fn next_day_starts_at_configured_hour() {
+    let limits = DailyLimits::default();
+    let now = SystemTime::now();
+    let next = limits.next_day_start(now);
+    assert!(next > now);
+    assert_eq!(limits.day(next), limits.day(now).succ_opt().unwrap());
+}
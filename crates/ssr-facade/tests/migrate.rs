@@ -0,0 +1,32 @@
+use std::time::{Duration, SystemTime};
+
+use common::{facade, session};
+use ssr_algorithms::super_memory_2::WriteAnswer;
+use ssr_core::{task::Task, tasks_facade::TasksFacade};
+use ssr_facade::stateful::Facade;
+
+mod common;
+
+#[test]
+fn keeps_ids_flags_and_progress() {
+    let (mut facade, ids) = facade("migrate", 3);
+    facade.set_suspended(ids[2], true);
+    let until = SystemTime::now() + Duration::from_secs(60 * 60);
+    facade.bury(ids[1], Some(until));
+    facade.set_tags(ids[0], vec!["tag".to_owned()]);
+    assert_eq!(session(&mut facade, &ids), [0]);
+
+    let migrated: Facade<WriteAnswer> = facade.migrate(());
+    let mut tasks = migrated
+        .iter_with_flags()
+        .map(|(task, id, flags)| (id, task.history().len(), flags.clone()))
+        .collect::<Vec<_>>();
+    tasks.sort_by_key(|(id, ..)| ids.iter().position(|x| x == id));
+    let (migrated_ids, reviews, flags): (Vec<_>, Vec<_>, Vec<_>) = tasks.into_iter().collect();
+    assert_eq!(migrated_ids, ids);
+    assert_eq!(reviews, [1, 0, 0]);
+    assert!(!flags[0].suspended && flags[2].suspended);
+    assert_eq!(flags[1].buried_until, Some(until));
+    assert_eq!(migrated.tags(ids[0]), Some(&["tag".to_owned()][..]));
+    assert_eq!(migrated.daily_counts(), facade.daily_counts());
+}
@@ -1,12 +1,60 @@
-type Task = ssr_algorithms::fsrs::Task;
+use anyhow::{Context, Result, ensure};
+use clap::{Parser, ValueEnum};
+use ssr_core::{
+    storage::{JsonFile, Sqlite, Storage},
+    tasks_facade::TasksFacade,
+};
+
+type Task = ssr_algorithms::any::Task;
 type Facade<'a> = ssr_facade::stateful::Facade<'a, Task>;
-const PATH: &str = "storage.json";
+/// Same place, where `ssr` keeps its decks.
+const DECKS_DIR: &str = "decks";
+
+#[derive(Parser, Debug)]
+struct Args {
+    /// Storage format of deck, the same as used by `ssr`.
+    #[arg(long, value_enum, default_value_t = StorageKind::Json)]
+    storage: StorageKind,
+    /// Deck, that tasks are added to. It must be created by `ssr deck create` first.
+    #[arg(long, default_value = "default")]
+    deck: String,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum StorageKind {
+    Json,
+    Sqlite,
+}
 
-use ssr_core::tasks_facade::TasksFacade;
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let extension = match args.storage {
+        StorageKind::Json => "json",
+        StorageKind::Sqlite => "sqlite",
+    };
+    let path = std::path::Path::new(DECKS_DIR).join(format!("{}.{extension}", args.deck));
+    ensure!(
+        path.exists(),
+        "there is no deck named '{}' in {extension} storage, create it with `ssr deck create`",
+        args.deck
+    );
 
-fn main() {
-    let file = std::fs::read_to_string(PATH).unwrap();
-    let mut facade: Facade = serde_json::from_str(&file).unwrap();
+    let mut buffer = String::new();
+    let mut storage: Box<dyn Storage<Facade>> = match args.storage {
+        StorageKind::Json => Box::new(JsonFile::new(&path)),
+        StorageKind::Sqlite => Box::new(Sqlite::open(&path)?),
+    };
+    let mut facade = storage
+        .load(&mut buffer)
+        .with_context(|| match args.storage {
+            StorageKind::Json => format!(
+                "refusing to start, fix '{}' or restore it from backup '{}'",
+                path.display(),
+                JsonFile::new(&path).backup_path(1).display()
+            ),
+            StorageKind::Sqlite => format!("refusing to start, fix '{}'", path.display()),
+        })?
+        .with_context(|| format!("deck '{}' is empty, fix '{}'", args.deck, path.display()))?;
     let items = [
         "arms",      // 0
         "eyebrows",  // 1
@@ -50,9 +98,12 @@ fn main() {
             .into_iter()
             .map(|a| vec![vec![], vec![a.to_string()]])
             .collect::<Vec<_>>();
-        let task = Task::new(blocks, first_answer, other_answers);
-        facade.insert(task);
+        let task = ssr_algorithms::fsrs::Task::new(blocks, first_answer, other_answers);
+        facade.insert(Task::Fsrs(task));
     }
+    facade.replace_tasks(Task::migrate);
 
-    std::fs::write(PATH, serde_json::to_string_pretty(&facade).unwrap()).unwrap();
+    storage
+        .save(&facade)
+        .with_context(|| format!("can't save '{}'", path.display()))
 }
@@ -0,0 +1,399 @@
+use std::{cmp::Ordering, time::SystemTime};
+
+use anyhow::Result;
+use chrono::{DateTime, Days, Local, NaiveDate};
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Cell, Row as TableRow, Table, TableState},
+};
+use s_text_input_f::{BlocksWithAnswer, ParagraphItem};
+use ssr_core::{
+    task::Task,
+    tasks_facade::{TaskId, TasksFacade},
+};
+
+use crate::{decks::Deck, stats::task_label};
+
+const PAGE: u16 = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Sort {
+    Text,
+    /// Not started tasks are the last.
+    Due,
+    /// The hardest first.
+    Difficulty,
+}
+
+impl Sort {
+    fn next(self) -> Self {
+        match self {
+            Self::Text => Self::Due,
+            Self::Due => Self::Difficulty,
+            Self::Difficulty => Self::Text,
+        }
+    }
+    fn name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Due => "due",
+            Self::Difficulty => "difficulty",
+        }
+    }
+}
+
+struct Row {
+    id: TaskId,
+    label: String,
+    tags: String,
+    /// Lowercase text of paragraphs, choices, answers and tags.
+    haystack: String,
+    /// `None` for not started and suspended tasks.
+    due: Option<SystemTime>,
+    suspended: bool,
+    buried: bool,
+    stability: Option<f32>,
+    difficulty: Option<f32>,
+    lapses: usize,
+}
+
+fn haystack(blocks: &BlocksWithAnswer) -> String {
+    let mut text = Vec::new();
+    for (block, answer) in blocks.blocks.iter().zip(&blocks.answer) {
+        match block {
+            s_text_input_f::Block::Paragraph(items) => {
+                text.extend(items.iter().filter_map(|item| match item {
+                    ParagraphItem::Text(text) => Some(text.as_str()),
+                    ParagraphItem::Placeholder => None,
+                }));
+                text.extend(answer.iter().map(String::as_str));
+            }
+            s_text_input_f::Block::OneOf(items)
+            | s_text_input_f::Block::AnyOf(items)
+            | s_text_input_f::Block::Order(items) => text.extend(items.iter().map(String::as_str)),
+            _ => (),
+        }
+    }
+    text.join(" ").to_lowercase()
+}
+
+fn rows(deck: &Deck) -> Vec<Row> {
+    let facade = &deck.facade;
+    let state = facade.shared_state();
+    let fsrs = state.weights().fsrs();
+    let now = SystemTime::now();
+    facade
+        .iter_with_flags()
+        .map(|(task, id, flags)| {
+            let blocks = task.get_blocks();
+            let level = task.fsrs().map(|task| task.level());
+            let memory_state = level.and_then(|level| level.memory_state(&fsrs));
+            let tags = facade.tags(id).unwrap_or_default().join(" ");
+            Row {
+                id,
+                label: task_label(&blocks.blocks),
+                haystack: format!("{} {}", haystack(&blocks), tags.to_lowercase()),
+                tags,
+                due: (!task.is_new() || flags.rescheduled_to.is_some())
+                    .then(|| {
+                        flags.apply(task.next_repetition(state, facade.get_desired_retention()))
+                    })
+                    .flatten(),
+                suspended: flags.suspended,
+                buried: flags.buried_until.is_some_and(|x| x > now),
+                stability: memory_state.map(|x| x.stability),
+                difficulty: memory_state.map(|x| x.difficulty),
+                lapses: level.map_or(0, |level| level.lapses()),
+            }
+        })
+        .collect()
+}
+
+/// Rows containing every word of `query`, sorted.
+fn visible(mut rows: Vec<Row>, query: &str, sort: Sort) -> Vec<Row> {
+    let words = query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    rows.retain(|row| words.iter().all(|word| row.haystack.contains(word)));
+    rows.sort_by(|a, b| {
+        let order = match sort {
+            Sort::Text => Ordering::Equal,
+            Sort::Due => match (a.due, b.due) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            Sort::Difficulty => b
+                .difficulty
+                .unwrap_or(f32::NEG_INFINITY)
+                .total_cmp(&a.difficulty.unwrap_or(f32::NEG_INFINITY)),
+        };
+        order.then(a.label.cmp(&b.label)).then(a.id.cmp(&b.id))
+    });
+    rows
+}
+
+enum Mode {
+    Normal,
+    Search,
+    ConfirmDelete(TaskId),
+    ConfirmReset(TaskId),
+    /// Typed date.
+    Reschedule(TaskId, String),
+}
+
+fn start_of_day(date: NaiveDate) -> Option<SystemTime> {
+    let time = date.and_hms_opt(0, 0, 0)?.and_local_timezone(Local);
+    Some(time.earliest()?.into())
+}
+
+/// Start of day, given as `YYYY-MM-DD` or as number of days from today.
+fn parse_date(input: &str) -> Option<SystemTime> {
+    let input = input.trim();
+    let date = match input.parse::<u64>() {
+        Ok(days) => Local::now()
+            .date_naive()
+            .checked_add_days(Days::new(days))?,
+        Err(_) => NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()?,
+    };
+    start_of_day(date)
+}
+
+fn render(
+    frame: &mut Frame,
+    deck: &Deck,
+    rows: &[Row],
+    state: &mut TableState,
+    (query, sort, mode): (&str, Sort, &Mode),
+) {
+    let [search, table, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let search_line = Line::from(vec![Span::from("Search: ").bold(), Span::from(query)]);
+    frame.render_widget(
+        if matches!(mode, Mode::Search) {
+            search_line.yellow()
+        } else {
+            search_line
+        },
+        search,
+    );
+
+    let header = TableRow::new(["Task", "Tags", "Due", "Stability", "Lapses", "ID"]).bold();
+    let table_rows = rows.iter().map(|row| {
+        let due = if row.suspended {
+            "suspended".to_owned()
+        } else if row.buried {
+            "buried".to_owned()
+        } else {
+            row.due.map_or_else(
+                || "new".to_owned(),
+                |due| DateTime::<Local>::from(due).format("%Y-%m-%d").to_string(),
+            )
+        };
+        let stability = row
+            .stability
+            .map_or_else(String::new, |x| format!("{x:.1}d"));
+        let row_style = if row.suspended {
+            Style::new().dark_gray()
+        } else {
+            Style::new()
+        };
+        TableRow::new([
+            Cell::from(row.label.clone()),
+            Cell::from(row.tags.clone()).cyan(),
+            Cell::from(due),
+            Cell::from(stability),
+            Cell::from(row.lapses.to_string()),
+            Cell::from(row.id.to_string()).dark_gray(),
+        ])
+        .style(row_style)
+    });
+    frame.render_stateful_widget(
+        Table::new(
+            table_rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Max(20),
+                Constraint::Length(10),
+                Constraint::Length(9),
+                Constraint::Length(6),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(Block::bordered().title(format!(
+            "Tasks of '{}' ({}/{}), sorted by {}",
+            deck.facade.get_name(),
+            rows.len(),
+            deck.facade.tasks_total(),
+            sort.name()
+        )))
+        .row_highlight_style(Style::new().reversed()),
+        table,
+        state,
+    );
+
+    let help = match mode {
+        Mode::Normal => Line::from(
+            "/ search, s sort, Enter edit, d delete, p suspend, b bury, r reschedule, f forget, Esc return"
+                .dark_gray()
+                .italic(),
+        ),
+        Mode::Search => Line::from(
+            "type to search, Enter or Esc to finish"
+                .dark_gray()
+                .italic(),
+        ),
+        Mode::ConfirmDelete(id) => Line::from(format!("Delete task {id}? y/n").red()),
+        Mode::ConfirmReset(id) => {
+            Line::from(format!("Forget progress of task {id}? y/n").red())
+        }
+        Mode::Reschedule(_, input) => {
+            let hint = match parse_date(input) {
+                Some(date) => DateTime::<Local>::from(date)
+                    .format("%Y-%m-%d")
+                    .to_string()
+                    .green(),
+                None => "YYYY-MM-DD or days from today".dark_gray().italic(),
+            };
+            Line::from(vec![
+                Span::from("Due: ").bold(),
+                Span::from(input.as_str()),
+                Span::from("  "),
+                hint,
+            ])
+        }
+    };
+    frame.render_widget(help, footer);
+}
+
+/// Replaces task with edited one. Progress is kept.
+fn edit(terminal: &mut Terminal<impl Backend>, deck: &mut Deck, id: TaskId) -> Result<()> {
+    let Some(blocks) = deck
+        .facade
+        .iter()
+        .find(|(_, x)| *x == id)
+        .map(|(task, _)| task.get_blocks())
+    else {
+        return Ok(());
+    };
+    let source = s_text_input_f_parser::to_source(&blocks);
+    if let Some(edited) = crate::get_blocks_with_answer(terminal, &source)?
+        && edited != blocks
+        && edited.answer.iter().any(|x| !x.is_empty())
+    {
+        deck.facade.update_blocks(id, edited);
+        deck.save_task(id)?;
+    }
+    Ok(())
+}
+
+/// Lists tasks of `deck` with incremental search. Selected task can be edited, deleted,
+/// suspended, buried until tomorrow, rescheduled or reset to new.
+pub fn show(terminal: &mut Terminal<impl Backend>, deck: &mut Deck) -> Result<()> {
+    let mut state = TableState::default().with_selected(Some(0));
+    let mut query = String::new();
+    let mut sort = Sort::Text;
+    let mut mode = Mode::Normal;
+    loop {
+        let rows = visible(rows(deck), &query, sort);
+        if state.selected().is_some_and(|x| x >= rows.len()) {
+            state.select(rows.len().checked_sub(1));
+        } else if state.selected().is_none() && !rows.is_empty() {
+            state.select(Some(0));
+        }
+        terminal.draw(|frame| render(frame, deck, &rows, &mut state, (&query, sort, &mode)))?;
+        let Event::Key(key) = crossterm::event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let selected_row = state.selected().and_then(|i| rows.get(i));
+        let selected = selected_row.map(|x| x.id);
+        match (&mut mode, key.code) {
+            (Mode::Search, KeyCode::Char(c)) => query.push(c),
+            (Mode::Search, KeyCode::Backspace) => {
+                query.pop();
+            }
+            (Mode::Search, KeyCode::Enter | KeyCode::Esc) => mode = Mode::Normal,
+            (Mode::ConfirmDelete(id), KeyCode::Char('y' | 'Y')) => {
+                deck.facade.remove(*id);
+                deck.save_task(*id)?;
+                mode = Mode::Normal;
+            }
+            (Mode::ConfirmDelete(_), _) => mode = Mode::Normal,
+            (Mode::ConfirmReset(id), KeyCode::Char('y' | 'Y')) => {
+                deck.facade.reset(*id);
+                deck.save_task(*id)?;
+                mode = Mode::Normal;
+            }
+            (Mode::ConfirmReset(_), _) => mode = Mode::Normal,
+            (Mode::Reschedule(_, input), KeyCode::Char(c)) => input.push(c),
+            (Mode::Reschedule(_, input), KeyCode::Backspace) => {
+                input.pop();
+            }
+            (Mode::Reschedule(id, input), KeyCode::Enter) => {
+                if let Some(due) = parse_date(input) {
+                    deck.facade.reschedule(*id, due);
+                    deck.save_task(*id)?;
+                    mode = Mode::Normal;
+                }
+            }
+            (Mode::Reschedule(..), KeyCode::Esc) => mode = Mode::Normal,
+            (Mode::Reschedule(..), _) => (),
+            (_, KeyCode::Up) => state.select_previous(),
+            (_, KeyCode::Down) => state.select_next(),
+            (_, KeyCode::PageUp) => state.scroll_up_by(PAGE),
+            (_, KeyCode::PageDown) => state.scroll_down_by(PAGE),
+            (Mode::Normal, KeyCode::Home) => state.select_first(),
+            (Mode::Normal, KeyCode::End) => state.select_last(),
+            (Mode::Normal, KeyCode::Char('/')) => mode = Mode::Search,
+            (Mode::Normal, KeyCode::Char('s')) => sort = sort.next(),
+            (Mode::Normal, KeyCode::Enter | KeyCode::Char('e')) => {
+                if let Some(id) = selected {
+                    edit(terminal, deck, id)?;
+                }
+            }
+            (Mode::Normal, KeyCode::Char('d') | KeyCode::Delete) => {
+                if let Some(id) = selected {
+                    mode = Mode::ConfirmDelete(id);
+                }
+            }
+            (Mode::Normal, KeyCode::Char('p')) => {
+                if let Some(row) = selected_row {
+                    deck.facade.set_suspended(row.id, !row.suspended);
+                    deck.save_task(row.id)?;
+                }
+            }
+            (Mode::Normal, KeyCode::Char('b')) => {
+                if let Some(row) = selected_row {
+                    let until = (!row.buried)
+                        .then(|| deck.facade.daily_limits().next_day_start(SystemTime::now()));
+                    deck.facade.bury(row.id, until);
+                    deck.save_task(row.id)?;
+                }
+            }
+            (Mode::Normal, KeyCode::Char('r')) => {
+                if let Some(id) = selected {
+                    mode = Mode::Reschedule(id, String::new());
+                }
+            }
+            (Mode::Normal, KeyCode::Char('f')) => {
+                if let Some(id) = selected {
+                    mode = Mode::ConfirmReset(id);
+                }
+            }
+            (Mode::Normal, KeyCode::Esc | KeyCode::Char('q')) => return Ok(()),
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result, bail, ensure};
+use ssr_algorithms::fsrs::simulator::{Simulation, SimulationConfig, retention_curve};
+use ssr_core::{
+    review_log::{ReviewLogEntry, Undo},
+    storage::{self, JsonFile, Sqlite, Storage},
+    tasks_facade::{TaskId, TasksFacade},
+};
+
+use crate::{DEFAULT_DESIRED_RETENTION, Facade, StorageKind};
+
+/// Storage files from before decks were introduced. Imported as [`DEFAULT_DECK`].
+const LEGACY_PATH: &str = "storage.json";
+const LEGACY_SQLITE_PATH: &str = "storage.sqlite";
+pub const DEFAULT_DECK: &str = "default";
+
+/// Named decks, each one is separate facade stored in its own file inside `dir`.
+pub struct Decks {
+    dir: PathBuf,
+    kind: StorageKind,
+}
+
+pub struct Deck<'a> {
+    pub facade: Facade<'a>,
+    storage: Box<dyn Storage<'a, Facade<'a>>>,
+    retention_curve: Option<RetentionCurve>,
+}
+
+/// Simulations of deck and what they were simulated with.
+struct RetentionCurve {
+    parameters: Vec<f32>,
+    config: SimulationConfig,
+    simulations: Vec<Simulation>,
+}
+
+impl Deck<'_> {
+    pub fn save(&mut self) -> Result<()> {
+        Ok(self.storage.save(&self.facade)?)
+    }
+    /// Saves reviewed task and appends review to the log.
+    pub fn save_review(&mut self, entry: &ReviewLogEntry) -> Result<()> {
+        Ok(self.storage.save_review(&self.facade, entry)?)
+    }
+    /// Saves single changed task.
+    pub fn save_task(&mut self, task_id: TaskId) -> Result<()> {
+        Ok(self.storage.save_task(&self.facade, task_id)?)
+    }
+    pub fn reviews(&mut self) -> Result<Vec<ReviewLogEntry>> {
+        Ok(self.storage.reviews()?)
+    }
+    /// Simulations for FSRS weights of deck, see [`retention_curve`].
+    /// Simulating takes a while, so it's done again only if weights or size of deck changed,
+    /// `on_simulation` is called before it.
+    pub fn retention_curve(
+        &mut self,
+        on_simulation: impl FnOnce(&Facade) -> Result<()>,
+    ) -> Result<&[Simulation]> {
+        let weights = self.facade.shared_state().weights();
+        let config =
+            SimulationConfig::for_deck(self.facade.tasks_total(), self.facade.daily_limits().new);
+        let curve = match self.retention_curve.take() {
+            Some(curve) if curve.parameters == weights.parameters() && curve.config == config => {
+                curve
+            }
+            _ => {
+                on_simulation(&self.facade)?;
+                RetentionCurve {
+                    parameters: weights.parameters().to_vec(),
+                    config,
+                    simulations: retention_curve(&weights.fsrs(), &config),
+                }
+            }
+        };
+        Ok(&self.retention_curve.insert(curve).simulations)
+    }
+    /// Saves task restored by undo and records undo in the review log.
+    pub fn save_undo(&mut self, task_id: TaskId) -> Result<()> {
+        let undo = Undo {
+            task_id,
+            timestamp: SystemTime::now(),
+        };
+        Ok(self.storage.save_undo(&self.facade, &undo)?)
+    }
+}
+
+fn extension(kind: StorageKind) -> &'static str {
+    match kind {
+        StorageKind::Json => "json",
+        StorageKind::Sqlite => "sqlite",
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    ensure!(!name.trim().is_empty(), "deck name must not be empty");
+    ensure!(
+        !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.')),
+        "deck name '{name}' must consist of letters, digits, spaces, '-', '_' and '.', and must not start with '.'"
+    );
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+impl Decks {
+    pub fn new(dir: impl Into<PathBuf>, kind: StorageKind) -> Self {
+        Self {
+            dir: dir.into(),
+            kind,
+        }
+    }
+
+    fn path_of(&self, name: &str, kind: StorageKind) -> PathBuf {
+        self.dir.join(format!("{name}.{}", extension(kind)))
+    }
+    fn path(&self, name: &str) -> PathBuf {
+        self.path_of(name, self.kind)
+    }
+
+    /// Names of all decks in any storage format, sorted.
+    pub fn names(&self) -> Result<Vec<String>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err).context(format!("can't read '{}'", self.dir.display())),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            let is_deck = path.extension().is_some_and(|found| {
+                [StorageKind::Json, StorageKind::Sqlite]
+                    .into_iter()
+                    .any(|kind| found == extension(kind))
+            });
+            if let (true, Some(name)) = (is_deck, path.file_stem().and_then(|x| x.to_str())) {
+                names.push(name.to_owned());
+            }
+        }
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        [StorageKind::Json, StorageKind::Sqlite]
+            .into_iter()
+            .any(|kind| self.path_of(name, kind).exists())
+    }
+
+    /// Opens deck storage in configured format.
+    /// `SQLite` storage imports JSON file of the same deck on first use.
+    fn open<'a>(&self, name: &str) -> Result<Box<dyn Storage<'a, Facade<'a>>>> {
+        let path = self.path(name);
+        Ok(match self.kind {
+            StorageKind::Json => Box::new(JsonFile::new(path)),
+            StorageKind::Sqlite => {
+                let mut sqlite = Sqlite::open(&path)?;
+                let json_path = self.path_of(name, StorageKind::Json);
+                if storage::migrate::<Facade>(
+                    &mut JsonFile::new(&json_path),
+                    &mut sqlite,
+                    &mut String::new(),
+                )? {
+                    println!(
+                        "Imported '{}' into '{}'.",
+                        json_path.display(),
+                        path.display()
+                    );
+                }
+                Box::new(sqlite)
+            }
+        })
+    }
+
+    pub fn load<'a>(&self, name: &str, buffer: &'a mut String) -> Result<Deck<'a>> {
+        ensure!(self.exists(name), "there is no deck named '{name}'");
+        ensure!(
+            self.kind == StorageKind::Sqlite || self.path(name).exists(),
+            "deck '{name}' is stored in SQLite, use `--storage sqlite`"
+        );
+        let mut storage = self.open(name)?;
+        let path = self.path(name);
+        let mut facade = storage
+            .load(buffer)
+            .with_context(|| match self.kind {
+                StorageKind::Json => format!(
+                    "refusing to start, fix '{}' or restore it from backup '{}'",
+                    path.display(),
+                    JsonFile::new(&path).backup_path(1).display()
+                ),
+                StorageKind::Sqlite => format!("refusing to start, fix '{}'", path.display()),
+            })?
+            .unwrap_or_else(|| Facade::new(name.to_owned(), DEFAULT_DESIRED_RETENTION));
+        facade.find_tasks_to_recall();
+        Ok(Deck {
+            facade,
+            storage,
+            retention_curve: None,
+        })
+    }
+
+    pub fn create(&self, name: &str) -> Result<()> {
+        validate_name(name)?;
+        ensure!(!self.exists(name), "deck '{name}' already exists");
+        fs::create_dir_all(&self.dir)?;
+        self.open(name)?
+            .save(&Facade::new(name.to_owned(), DEFAULT_DESIRED_RETENTION))?;
+        Ok(())
+    }
+
+    pub fn rename(&self, name: &str, new_name: &str) -> Result<()> {
+        validate_name(new_name)?;
+        ensure!(self.exists(name), "there is no deck named '{name}'");
+        ensure!(!self.exists(new_name), "deck '{new_name}' already exists");
+        for kind in [StorageKind::Json, StorageKind::Sqlite] {
+            let from = self.path_of(name, kind);
+            if !from.exists() {
+                continue;
+            }
+            let to = self.path_of(new_name, kind);
+            fs::rename(&from, &to)?;
+            if kind == StorageKind::Json {
+                let (from, to) = (JsonFile::new(from), JsonFile::new(&to));
+                for n in 1..=JsonFile::DEFAULT_BACKUPS {
+                    if from.backup_path(n).exists() {
+                        fs::rename(from.backup_path(n), to.backup_path(n))?;
+                    }
+                }
+                if from.review_log_path().exists() {
+                    fs::rename(from.review_log_path(), to.review_log_path())?;
+                }
+            }
+            let mut buffer = String::new();
+            let mut storage: Box<dyn Storage<Facade>> = match kind {
+                StorageKind::Json => Box::new(JsonFile::new(&to)),
+                StorageKind::Sqlite => Box::new(Sqlite::open(&to)?),
+            };
+            if let Some(mut facade) = storage.load(&mut buffer)? {
+                facade.set_name(new_name.to_owned());
+                storage.save(&facade)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes deck in every storage format together with its backups and review log.
+    pub fn delete(&self, name: &str) -> Result<()> {
+        ensure!(self.exists(name), "there is no deck named '{name}'");
+        let json = JsonFile::new(self.path_of(name, StorageKind::Json));
+        remove_if_exists(json.path())?;
+        for n in 1..=JsonFile::DEFAULT_BACKUPS {
+            remove_if_exists(&json.backup_path(n))?;
+        }
+        remove_if_exists(&json.review_log_path())?;
+        remove_if_exists(&self.path_of(name, StorageKind::Sqlite))?;
+        Ok(())
+    }
+
+    /// Imports storage from before decks were introduced as [`DEFAULT_DECK`], if there are no
+    /// decks yet. Legacy file is left in place.
+    pub fn import_legacy(&self) -> Result<()> {
+        if !self.names()?.is_empty() {
+            return Ok(());
+        }
+        let mut buffer = String::new();
+        let mut legacy: Box<dyn Storage<Facade>> = match self.kind {
+            StorageKind::Sqlite if Path::new(LEGACY_SQLITE_PATH).exists() => {
+                Box::new(Sqlite::open(LEGACY_SQLITE_PATH)?)
+            }
+            _ => Box::new(JsonFile::new(LEGACY_PATH)),
+        };
+        let Some(mut facade) = legacy
+            .load(&mut buffer)
+            .context("can't import storage from previous version")?
+        else {
+            return Ok(());
+        };
+        facade.set_name(DEFAULT_DECK.to_owned());
+        fs::create_dir_all(&self.dir)?;
+        self.open(DEFAULT_DECK)?.save(&facade)?;
+        println!(
+            "Imported previous storage into deck '{DEFAULT_DECK}' ('{}').",
+            self.path(DEFAULT_DECK).display()
+        );
+        Ok(())
+    }
+
+    /// Deck, that commands without `--deck` operate on: the only one, or newly created
+    /// [`DEFAULT_DECK`], if there are none.
+    pub fn implicit(&self) -> Result<String> {
+        let mut names = self.names()?;
+        match names.len() {
+            0 => {
+                self.create(DEFAULT_DECK)?;
+                Ok(DEFAULT_DECK.to_owned())
+            }
+            1 => Ok(names.remove(0)),
+            _ => bail!(
+                "there are several decks ({}), choose one with --deck",
+                names.join(", ")
+            ),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::{io::Write, time::SystemTime};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use s_text_input_f::{Block, BlocksWithAnswer, ParagraphItem};
+use s_text_input_f_parser::front_matter::FrontMatter;
+use ssr_core::{
+    task::Task,
+    tasks_facade::{TaskId, TasksFacade},
+};
+
+use crate::Facade;
+
+/// Line between tasks in markdown export.
+pub const TASK_SEPARATOR: &str = "---";
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// Columns: front, back, tags (space separated), due, stability (in days).
+    Csv,
+    /// Same as csv, but separated by tabs.
+    Tsv,
+    /// Text file for Anki "Import File": tasks with placeholders become cloze notes,
+    /// other tasks become basic notes.
+    Anki,
+    /// Tasks in `ssr add` syntax, separated by `---` lines. Can be used as `ssr sync` source.
+    Markdown,
+}
+
+pub struct Exported {
+    pub tasks: usize,
+    /// Tasks, that can't be represented in chosen format.
+    pub skipped: Vec<TaskId>,
+}
+
+/// Writes all tasks of `facade` to `output`.
+pub fn export(facade: &Facade, format: Format, output: impl Write) -> Result<Exported> {
+    match format {
+        Format::Csv => table(facade, b',', output),
+        Format::Tsv => table(facade, b'\t', output),
+        Format::Anki => anki(facade, output),
+        Format::Markdown => markdown(facade, output),
+    }
+}
+
+fn table(facade: &Facade, delimiter: u8, output: impl Write) -> Result<Exported> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(output);
+    writer.write_record(["front", "back", "tags", "due", "stability"])?;
+    let state = facade.shared_state();
+    let fsrs = state.weights().fsrs();
+    let mut tasks = 0;
+    for (task, id) in facade.iter() {
+        let blocks = task.get_blocks();
+        let due = if task.is_new() {
+            String::new()
+        } else {
+            format_time(task.next_repetition(state, facade.get_desired_retention()))
+        };
+        let stability = task
+            .fsrs()
+            .and_then(|task| task.level().memory_state(&fsrs))
+            .map(|memory_state| format!("{:.2}", memory_state.stability))
+            .unwrap_or_default();
+        writer.write_record([
+            front(&blocks),
+            back(&blocks),
+            tags(facade, id),
+            due,
+            stability,
+        ])?;
+        tasks += 1;
+    }
+    writer.flush()?;
+    Ok(Exported {
+        tasks,
+        skipped: Vec::new(),
+    })
+}
+
+/// Space separated, as Anki expects.
+fn tags(facade: &Facade, id: TaskId) -> String {
+    facade.tags(id).unwrap_or_default().join(" ")
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// Question side: placeholders are shown as `___`, choices are listed.
+fn front(blocks: &BlocksWithAnswer) -> String {
+    blocks
+        .blocks
+        .iter()
+        .map(|block| match block {
+            Block::Paragraph(items) => items
+                .iter()
+                .map(|item| match item {
+                    ParagraphItem::Text(text) => text.as_str(),
+                    ParagraphItem::Placeholder => "___",
+                })
+                .collect(),
+            Block::OneOf(items) | Block::AnyOf(items) | Block::Order(items) => items
+                .iter()
+                .map(|item| format!("- {item}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Answer side: paragraphs with filled placeholders, correct choices and correct order.
+fn back(blocks: &BlocksWithAnswer) -> String {
+    blocks
+        .blocks
+        .iter()
+        .zip(&blocks.answer)
+        .map(|(block, answer)| match block {
+            Block::Paragraph(items) => fill_placeholders(items, answer, |x| x.to_owned()),
+            Block::OneOf(items) | Block::AnyOf(items) | Block::Order(items) => answer
+                .iter()
+                .filter_map(|i| items.get(i.parse::<usize>().ok()?))
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fill_placeholders(
+    items: &[ParagraphItem],
+    answer: &[String],
+    mut fill: impl FnMut(&str) -> String,
+) -> String {
+    let mut answer = answer.iter();
+    items
+        .iter()
+        .map(|item| match item {
+            ParagraphItem::Text(text) => text.clone(),
+            ParagraphItem::Placeholder => fill(answer.next().map_or("", String::as_str)),
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+/// Notes for Anki text import. File headers tell Anki separator, note type and tags columns.
+fn anki(facade: &Facade, mut output: impl Write) -> Result<Exported> {
+    writeln!(output, "#separator:tab")?;
+    writeln!(output, "#html:true")?;
+    writeln!(output, "#notetype column:1")?;
+    writeln!(output, "#tags column:4")?;
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .from_writer(output);
+    let mut tasks = 0;
+    for (task, id) in facade.iter() {
+        let blocks = task.get_blocks();
+        let has_placeholders = blocks.blocks.iter().any(|block| {
+            matches!(block, Block::Paragraph(items)
+                if items.iter().any(|x| matches!(x, ParagraphItem::Placeholder)))
+        });
+        let record = if has_placeholders {
+            // All placeholders are asked at once, so they share cloze number.
+            let mut text = Vec::new();
+            let mut extra = Vec::new();
+            for (block, answer) in blocks.blocks.iter().zip(&blocks.answer) {
+                let single = BlocksWithAnswer {
+                    blocks: vec![block.clone()],
+                    answer: vec![answer.clone()],
+                };
+                match block {
+                    Block::Paragraph(items) => {
+                        let items = items
+                            .iter()
+                            .map(|item| match item {
+                                ParagraphItem::Text(text) => ParagraphItem::Text(escape_html(text)),
+                                ParagraphItem::Placeholder => ParagraphItem::Placeholder,
+                            })
+                            .collect::<Vec<_>>();
+                        text.push(fill_placeholders(&items, answer, |x| {
+                            format!("{{{{c1::{}}}}}", escape_html(x))
+                        }));
+                    }
+                    _ => {
+                        text.push(escape_html(&front(&single)));
+                        extra.push(escape_html(&back(&single)));
+                    }
+                }
+            }
+            [
+                "Cloze".to_owned(),
+                text.join("<br>"),
+                extra.join("<br>"),
+                tags(facade, id),
+            ]
+        } else {
+            [
+                "Basic".to_owned(),
+                escape_html(&front(&blocks)),
+                escape_html(&back(&blocks)),
+                tags(facade, id),
+            ]
+        };
+        writer.write_record(record)?;
+        tasks += 1;
+    }
+    writer.flush()?;
+    Ok(Exported {
+        tasks,
+        skipped: Vec::new(),
+    })
+}
+
+/// Task in `s_text_input_f-parser` syntax, if it can be parsed back to the same task.
+fn to_markdown(blocks: &BlocksWithAnswer, tags: &[String]) -> Option<String> {
+    let front_matter = FrontMatter {
+        tags: tags.to_vec(),
+    };
+    let source = front_matter.to_source() + &s_text_input_f_parser::to_source(blocks);
+    let parsed = s_text_input_f_parser::parse_task(&source).ok()?;
+    (parsed.blocks == *blocks && parsed.front_matter == front_matter).then_some(source)
+}
+
+fn markdown(facade: &Facade, mut output: impl Write) -> Result<Exported> {
+    let mut exported = Exported {
+        tasks: 0,
+        skipped: Vec::new(),
+    };
+    for (task, id) in facade.iter() {
+        let Some(source) = to_markdown(&task.get_blocks(), facade.tags(id).unwrap_or_default())
+        else {
+            exported.skipped.push(id);
+            continue;
+        };
+        if exported.tasks > 0 {
+            writeln!(output, "\n{TASK_SEPARATOR}\n")?;
+        }
+        writeln!(output, "{source}")?;
+        exported.tasks += 1;
+    }
+    Ok(exported)
+}
@@ -1,10 +1,16 @@
 use std::{
-    io::{Write, stdout},
+    fs,
+    io::{self, stdout},
+    path::PathBuf,
     process::ExitCode,
+    time::Duration,
 };
 
-use anyhow::{Result, ensure};
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result, ensure};
+use clap::{Parser, Subcommand, ValueEnum};
+use decks::{Deck, Decks};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use ratatui::{
     Terminal,
     prelude::{CrosstermBackend, *},
@@ -12,70 +18,373 @@ use ratatui::{
 };
 use ratatui_inputs::ResultKind;
 use s_text_input_f::BlocksWithAnswer;
-use ssr_core::tasks_facade::TasksFacade;
+use ssr_algorithms::{
+    any::Algorithm,
+    fsrs::{
+        simulator::{Simulation, optimal_retention},
+        steps::{format_interval, parse_interval},
+        weights::{Evaluation, OptimizationReport},
+    },
+};
+use ssr_core::{review_log::ReviewLogEntry, tasks_facade::TasksFacade};
+use ssr_facade::order::Order;
+
+mod browser;
+mod decks;
+mod export;
+mod stats;
+mod sync;
 
-type Task = ssr_algorithms::fsrs::Task;
+type Task = ssr_algorithms::any::Task;
 type Facade<'a> = ssr_facade::stateful::Facade<'a, Task>;
 
 const DEFAULT_DESIRED_RETENTION: f64 = 0.85;
 
 #[derive(Parser, Debug)]
 struct Args {
+    /// Where tasks are stored. `sqlite` imports existing JSON decks on first run.
+    #[arg(long, value_enum, default_value_t = StorageKind::Json)]
+    storage: StorageKind,
+    /// Deck to work with. If omitted, it's asked on startup when there are several decks.
+    #[arg(long)]
+    deck: Option<String>,
+    /// Review only tasks with tags in this category, e.g. `rust` includes `rust::lifetimes`.
+    #[arg(long, value_parser = parse_tag)]
+    only: Option<String>,
+    /// Order of tasks in review session.
+    #[arg(long, value_enum, default_value_t = ReviewOrder::Random)]
+    order: ReviewOrder,
+    /// Seed of random choices in review session, the same seed gives the same order.
+    #[arg(long)]
+    seed: Option<u64>,
     #[command(subcommand)]
     action: Option<Action>,
 }
 
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum StorageKind {
+    Json,
+    Sqlite,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum ReviewOrder {
+    Random,
+    /// Task, that became due the earliest, first.
+    MostOverdue,
+    /// Task, that is the most likely to be forgotten, first.
+    LowestRetrievability,
+    /// Reviews and new tasks in turn.
+    NewInterleaved,
+    /// Top level tag categories in turn. In all decks scope decks take turns too.
+    RoundRobin,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+enum AlgorithmKind {
+    Fsrs,
+    #[value(name = "sm2")]
+    SuperMemory2,
+    Leitner,
+}
+
+impl From<AlgorithmKind> for Algorithm {
+    fn from(value: AlgorithmKind) -> Self {
+        match value {
+            AlgorithmKind::Fsrs => Algorithm::Fsrs,
+            AlgorithmKind::SuperMemory2 => Algorithm::SuperMemory2,
+            AlgorithmKind::Leitner => Algorithm::Leitner,
+        }
+    }
+}
+
+impl From<ReviewOrder> for Order {
+    fn from(value: ReviewOrder) -> Self {
+        match value {
+            ReviewOrder::Random => Order::Random,
+            ReviewOrder::MostOverdue => Order::MostOverdue,
+            ReviewOrder::LowestRetrievability => Order::LowestRetrievability,
+            ReviewOrder::NewInterleaved => Order::NewInterleaved,
+            ReviewOrder::RoundRobin => Order::TagRoundRobin,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 enum Action {
-    Add { content: String },
+    /// Add task. It can start with front matter: `+++`, `tags: a, b::c` and `+++` lines.
+    Add {
+        content: String,
+        /// Add tag to task, levels of hierarchical tags are separated by `::`.
+        #[arg(long = "tag", short, value_parser = parse_tag)]
+        tags: Vec<String>,
+    },
+    /// Manage decks.
+    #[command(subcommand)]
+    Deck(DeckAction),
+    /// Import tasks from other programs.
+    #[command(subcommand)]
+    Import(ImportAction),
+    /// Export tasks of deck.
+    Export {
+        #[arg(value_enum)]
+        format: export::Format,
+        /// File to write, standard output if omitted.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Make deck match directory of markdown files: tasks are separated by `---` lines and
+    /// identified by `<!-- ssr-id: ... -->` comments, which are added to new tasks.
+    Sync { dir: PathBuf },
+}
+
+#[derive(Subcommand, Debug)]
+enum ImportAction {
+    /// Import cards of Anki package (`.apkg` or `.colpkg`) together with their review history.
+    Anki { file: PathBuf },
+}
+
+#[derive(Subcommand, Debug)]
+enum DeckAction {
+    List,
+    Create {
+        name: String,
+    },
+    Rename {
+        name: String,
+        new_name: String,
+    },
+    /// Delete deck together with its backups and review log.
+    Delete {
+        name: String,
+    },
+    /// Show or change daily limits of deck. Tasks over limits wait for the next day.
+    Limits {
+        name: String,
+        /// New tasks introduced per day, `none` for no limit.
+        #[arg(long, value_parser = parse_limit)]
+        new: Option<Limit>,
+        /// All tasks completed per day, new ones included, `none` for no limit.
+        #[arg(long, value_parser = parse_limit)]
+        reviews: Option<Limit>,
+        /// Hour of local time (0-23), when the next day starts.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(0..24))]
+        day_starts_at: Option<u32>,
+    },
+    /// Show or change learning steps of deck, e.g. `--learning 1m,10m,1h`.
+    /// Tasks are scheduled by FSRS after the last step.
+    Steps {
+        name: String,
+        /// Steps of new tasks, `none` to skip learning.
+        #[arg(long, value_parser = parse_steps)]
+        learning: Option<StepList>,
+        /// Steps of forgotten tasks, `none` to skip relearning.
+        #[arg(long, value_parser = parse_steps)]
+        relearning: Option<StepList>,
+    },
+    /// Show or change scheduling algorithm of deck. Review history of tasks is replayed
+    /// into the new algorithm, so their progress is kept.
+    Algorithm {
+        name: String,
+        #[arg(value_enum)]
+        algorithm: Option<AlgorithmKind>,
+    },
+}
+
+/// Daily limit, `None` if unlimited.
+#[derive(Clone, Copy, Debug)]
+struct Limit(Option<usize>);
+
+fn parse_limit(input: &str) -> Result<Limit, String> {
+    if input == "none" {
+        return Ok(Limit(None));
+    }
+    input
+        .parse()
+        .map(|x| Limit(Some(x)))
+        .map_err(|_| "limit must be number or `none`".to_owned())
+}
+
+fn format_limit(limit: Option<usize>) -> String {
+    limit.map_or_else(|| "unlimited".to_owned(), |x| x.to_string())
+}
+
+#[derive(Clone, Debug)]
+struct StepList(Vec<Duration>);
+
+fn parse_steps(input: &str) -> Result<StepList, String> {
+    if input == "none" {
+        return Ok(StepList(Vec::new()));
+    }
+    input
+        .split(',')
+        .map(|step| {
+            parse_interval(step).ok_or_else(|| {
+                format!("invalid step `{step}`, use number with `s`, `m`, `h` or `d` unit")
+            })
+        })
+        .collect::<Result<_, _>>()
+        .map(StepList)
+}
+
+fn format_steps(steps: &[Duration]) -> String {
+    if steps.is_empty() {
+        return "none".to_owned();
+    }
+    steps
+        .iter()
+        .map(|&x| format_interval(x))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-const PATH: &str = "storage.json";
+const DECKS_DIR: &str = "decks";
 
 fn main() -> Result<ExitCode> {
     let args = Args::parse();
 
-    let mut storage = {
-        if std::fs::exists(PATH)? {
-            let file = Box::new(std::fs::read_to_string(PATH)?).leak();
-            serde_json::from_str(file)?
-        } else {
-            Facade::new("test_name".into(), DEFAULT_DESIRED_RETENTION)
-        }
-    };
+    let decks = Decks::new(DECKS_DIR, args.storage);
+    decks.import_legacy()?;
 
-    let success = if let Some(action) = args.action {
-        match action {
-            Action::Add { content } => {
-                let a = s_text_input_f_parser::parse_blocks(&content);
-                match a {
-                    Ok(blocks) => {
-                        if blocks.answer.iter().map(|x| x.len()).sum::<usize>() == 0 {
-                            println!("Task must contain interactive elements.");
-                            false
-                        } else {
-                            let task = Task::new(blocks.blocks, blocks.answer, Vec::new());
-                            storage.insert(task);
-                            println!("Task added");
-                            true
-                        }
-                    }
-                    Err(errs) => {
-                        for err in errs {
-                            println!("Parsing error: {err}.");
-                        }
-                        false
-                    }
+    let success = match args.action {
+        Some(Action::Add { content, tags }) => {
+            let name = match args.deck {
+                Some(name) => name,
+                None => decks.implicit()?,
+            };
+            let mut buffer = String::new();
+            let mut deck = decks.load(&name, &mut buffer)?;
+            let success = add_task(&mut deck.facade, &content, tags);
+            deck.save()?;
+            success
+        }
+        Some(Action::Deck(action)) => {
+            manage_decks(&decks, action)?;
+            true
+        }
+        Some(Action::Import(ImportAction::Anki { file })) => {
+            let name = match args.deck {
+                Some(name) => name,
+                None => decks.implicit()?,
+            };
+            let mut buffer = String::new();
+            let mut deck = decks.load(&name, &mut buffer)?;
+            let steps = deck.facade.shared_state().weights().steps();
+            let tasks = ssr_algorithms::fsrs::anki::import(&file, steps)
+                .with_context(|| format!("can't import '{}'", file.display()))?;
+            let imported = tasks.len();
+            for task in tasks {
+                deck.facade.insert(Task::Fsrs(task));
+            }
+            deck.facade.replace_tasks(Task::migrate);
+            deck.save()?;
+            println!("Imported {imported} tasks into deck '{name}'");
+            true
+        }
+        Some(Action::Export { format, output }) => {
+            let name = match args.deck {
+                Some(name) => name,
+                None => decks.implicit()?,
+            };
+            let mut buffer = String::new();
+            let deck = decks.load(&name, &mut buffer)?;
+            let exported = match &output {
+                Some(path) => export::export(
+                    &deck.facade,
+                    format,
+                    io::BufWriter::new(fs::File::create(path)?),
+                )?,
+                None => export::export(&deck.facade, format, stdout().lock())?,
+            };
+            if !exported.skipped.is_empty() {
+                eprintln!(
+                    "Skipped {} tasks, that can't be represented in {format:?} format: {}",
+                    exported.skipped.len(),
+                    exported
+                        .skipped
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            if let Some(path) = output {
+                println!(
+                    "Exported {} tasks of deck '{name}' to '{}'",
+                    exported.tasks,
+                    path.display()
+                );
+            }
+            true
+        }
+        Some(Action::Sync { dir }) => {
+            let name = match args.deck {
+                Some(name) => name,
+                None => decks.implicit()?,
+            };
+            let mut buffer = String::new();
+            let mut deck = decks.load(&name, &mut buffer)?;
+            let synced = sync::sync(&dir, &mut deck.facade)?;
+            deck.save()?;
+            synced.write_ids()?;
+            let summary = synced.summary;
+            println!(
+                "Deck '{name}' synced with '{}': {} created, {} updated, {} removed, {} unchanged",
+                dir.display(),
+                summary.created,
+                summary.updated,
+                summary.removed,
+                summary.unchanged
+            );
+            true
+        }
+        None => {
+            if decks.names()?.is_empty() {
+                decks.implicit()?;
+            }
+            let names = decks.names()?;
+            let scope = match &args.deck {
+                Some(name) => Scope::Deck(
+                    names
+                        .iter()
+                        .position(|x| x == name)
+                        .ok_or_else(|| anyhow::anyhow!("there is no deck named '{name}'"))?,
+                ),
+                None if names.len() == 1 => Scope::Deck(0),
+                None => Scope::All,
+            };
+            let mut buffers = vec![String::new(); names.len()];
+            let mut loaded = names
+                .iter()
+                .zip(buffers.iter_mut())
+                .map(|(name, buffer)| decks.load(name, buffer))
+                .collect::<Result<Vec<_>>>()?;
+            let pick_on_startup = args.deck.is_none() && loaded.len() > 1;
+            let order = Order::from(args.order);
+            for deck in &mut loaded {
+                deck.facade.set_session_filter(args.only.clone());
+                deck.facade.set_order(order);
+                if let Some(seed) = args.seed {
+                    deck.facade.set_seed(seed);
                 }
             }
+            let rng = args
+                .seed
+                .map_or_else(ChaCha8Rng::from_os_rng, ChaCha8Rng::seed_from_u64);
+            let session = Session {
+                filter: args.only,
+                order,
+                rng,
+            };
+            let result = application(&mut loaded, scope, pick_on_startup, session);
+            for deck in &mut loaded {
+                deck.save()?;
+            }
+            result?;
+            true
         }
-    } else {
-        application(&mut storage)?;
-        true
     };
 
-    save(PATH, &storage)?;
-
     if success {
         Ok(ExitCode::SUCCESS)
     } else {
@@ -83,29 +392,219 @@ fn main() -> Result<ExitCode> {
     }
 }
 
+fn parse_tag(input: &str) -> Result<String, String> {
+    s_text_input_f_parser::parse_tag(input).map_err(|_| {
+        "tag levels are separated by `::` and can't be empty or contain whitespace, commas and colons"
+            .to_owned()
+    })
+}
+
+fn add_task(facade: &mut Facade, content: &str, tags: Vec<String>) -> bool {
+    match s_text_input_f_parser::parse_task(content) {
+        Ok(parsed) => {
+            let blocks = parsed.blocks;
+            if blocks.answer.iter().map(|x| x.len()).sum::<usize>() == 0 {
+                println!("Task must contain interactive elements.");
+                false
+            } else {
+                let mut all_tags = parsed.front_matter.tags;
+                for tag in tags {
+                    if !all_tags.contains(&tag) {
+                        all_tags.push(tag);
+                    }
+                }
+                let id = facade.create_task(blocks);
+                facade.set_tags(id, all_tags);
+                println!("Task added to deck '{}'", facade.get_name());
+                true
+            }
+        }
+        Err(errs) => {
+            for err in errs {
+                println!("Parsing error: {err}.");
+            }
+            false
+        }
+    }
+}
+
+fn manage_decks(decks: &Decks, action: DeckAction) -> Result<()> {
+    match action {
+        DeckAction::List => {
+            for name in decks.names()? {
+                let mut buffer = String::new();
+                let deck = decks.load(&name, &mut buffer)?;
+                println!(
+                    "{name}: {} tasks, {} to complete, desired retention {:.0}%, {} ({} parameters)",
+                    deck.facade.tasks_total(),
+                    deck.facade.tasks_to_complete(),
+                    deck.facade.get_desired_retention() * 100.,
+                    deck.facade.shared_state().algorithm(),
+                    deck.facade.shared_state().weights().version()
+                );
+            }
+        }
+        DeckAction::Create { name } => {
+            decks.create(&name)?;
+            println!("Deck '{name}' created");
+        }
+        DeckAction::Rename { name, new_name } => {
+            decks.rename(&name, &new_name)?;
+            println!("Deck '{name}' renamed to '{new_name}'");
+        }
+        DeckAction::Delete { name } => {
+            decks.delete(&name)?;
+            println!("Deck '{name}' deleted");
+        }
+        DeckAction::Limits {
+            name,
+            new,
+            reviews,
+            day_starts_at,
+        } => {
+            let mut buffer = String::new();
+            let mut deck = decks.load(&name, &mut buffer)?;
+            let mut limits = deck.facade.daily_limits();
+            if let Some(Limit(new)) = new {
+                limits.new = new;
+            }
+            if let Some(Limit(reviews)) = reviews {
+                limits.reviews = reviews;
+            }
+            if let Some(hour) = day_starts_at {
+                limits.day_starts_at = hour;
+            }
+            if limits != deck.facade.daily_limits() {
+                deck.facade.set_daily_limits(limits);
+                deck.save()?;
+            }
+            let today = deck.facade.daily_counts();
+            println!(
+                "{name}: new tasks {} per day ({} today), reviews {} per day ({} today), day starts at {}:00",
+                format_limit(limits.new),
+                today.new,
+                format_limit(limits.reviews),
+                today.reviews,
+                limits.day_starts_at
+            );
+        }
+        DeckAction::Steps {
+            name,
+            learning,
+            relearning,
+        } => {
+            let mut buffer = String::new();
+            let mut deck = decks.load(&name, &mut buffer)?;
+            let mut state = deck.facade.shared_state().clone();
+            let mut weights = state.weights().clone();
+            let mut steps = weights.steps().clone();
+            if let Some(StepList(learning)) = learning {
+                steps.learning = learning;
+            }
+            if let Some(StepList(relearning)) = relearning {
+                steps.relearning = relearning;
+            }
+            if steps != *weights.steps() {
+                weights.set_steps(steps.clone());
+                state.set_weights(weights);
+                deck.facade.set_shared_state(state);
+                deck.save()?;
+            }
+            println!(
+                "{name}: learning steps {}, relearning steps {}",
+                format_steps(&steps.learning),
+                format_steps(&steps.relearning)
+            );
+        }
+        DeckAction::Algorithm { name, algorithm } => {
+            let mut buffer = String::new();
+            let mut deck = decks.load(&name, &mut buffer)?;
+            if let Some(algorithm) = algorithm.map(Algorithm::from)
+                && algorithm != deck.facade.shared_state().algorithm()
+            {
+                let mut state = deck.facade.shared_state().clone();
+                state.set_algorithm(algorithm);
+                deck.facade.set_shared_state(state);
+                deck.facade.replace_tasks(Task::migrate);
+                deck.save()?;
+            }
+            println!("{name}: {}", deck.facade.shared_state().algorithm());
+        }
+    }
+    Ok(())
+}
+
+/// Decks, that menu actions apply to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Scope {
+    Deck(usize),
+    /// Review queue combines tasks of all decks.
+    All,
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum Submenu {
     CompleteTask,
     CreateTask,
+    BrowseTasks,
     ModifyDesiredRetention,
     Optimize,
+    Statistics,
+    SwitchDeck,
+    FilterTags,
     Save,
 }
 
-fn application(storage: &mut Facade) -> Result<()> {
+/// Settings of review session, that aren't saved.
+struct Session {
+    /// Category of tags, that review is restricted to.
+    filter: Option<String>,
+    order: Order,
+    /// Chooses deck in all decks scope.
+    rng: ChaCha8Rng,
+}
+
+fn application(
+    decks: &mut [Deck],
+    mut scope: Scope,
+    pick_on_startup: bool,
+    mut session: Session,
+) -> Result<()> {
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     let alt = alternate_screen_wrapper::AlternateScreen::enter()?.bracketed_paste()?;
 
+    if pick_on_startup {
+        match pick_deck(&mut terminal, decks, true)? {
+            Some(picked) => scope = picked,
+            None => {
+                drop(alt);
+                return Ok(());
+            }
+        }
+    }
+
+    // Decks of reviews, that can be undone, the latest is last.
+    let mut reviewed = Vec::new();
+    let mut last_deck = None;
     loop {
+        let in_scope = |i: usize| scope == Scope::All || scope == Scope::Deck(i);
         let submenu = {
-            storage.find_tasks_to_recall();
+            for deck in decks.iter_mut() {
+                deck.facade.find_tasks_to_recall();
+            }
+            let to_complete: usize = (0..decks.len())
+                .filter(|&i| in_scope(i))
+                .map(|i| decks[i].facade.tasks_to_complete())
+                .sum();
             let request = vec![s_text_input_f::Block::OneOf(vec![
                 format!("complete task ({})", {
-                    let to_complete = storage.tasks_to_complete();
                     if to_complete > 0 {
                         to_complete.to_string()
                     } else {
-                        let until = storage.until_next_repetition();
+                        let until = (0..decks.len())
+                            .filter(|&i| in_scope(i))
+                            .filter_map(|i| decks[i].facade.until_next_repetition())
+                            .min();
                         if let Some(until) = until {
                             format!("0; {:.2}h", until.as_secs_f64() / 3600.)
                         } else {
@@ -114,11 +613,24 @@ fn application(storage: &mut Facade) -> Result<()> {
                     }
                 }),
                 "create task".into(),
-                format!(
-                    "desired retention ({:.0}%)",
-                    (storage.get_desired_retention() * 100.).floor()
-                ),
+                "browse tasks".into(),
+                match scope {
+                    Scope::Deck(i) => format!(
+                        "desired retention ({:.0}%)",
+                        (decks[i].facade.get_desired_retention() * 100.).floor()
+                    ),
+                    Scope::All => "desired retention".into(),
+                },
                 "optimize".into(),
+                "statistics".into(),
+                match scope {
+                    Scope::Deck(i) => format!("deck: {}", decks[i].facade.get_name()),
+                    Scope::All => "deck: all decks".into(),
+                },
+                match &session.filter {
+                    Some(category) => format!("tags: {category}"),
+                    None => "tags: all".into(),
+                },
                 "save".into(),
             ])];
             let (result_kind, answer) = ratatui_inputs::get_input(request, &mut |text| {
@@ -128,49 +640,279 @@ fn application(storage: &mut Facade) -> Result<()> {
             })
             .unwrap()?;
 
-            if result_kind == ResultKind::Canceled {
-                break;
+            match result_kind {
+                ResultKind::Canceled => break,
+                ResultKind::Undo => {
+                    undo(decks, &mut reviewed)?;
+                    continue;
+                }
+                _ => (),
             }
             let answer: usize = answer[0][0].parse()?;
             [
                 Submenu::CompleteTask,
                 Submenu::CreateTask,
+                Submenu::BrowseTasks,
                 Submenu::ModifyDesiredRetention,
                 Submenu::Optimize,
+                Submenu::Statistics,
+                Submenu::SwitchDeck,
+                Submenu::FilterTags,
                 Submenu::Save,
             ][answer]
         };
         match submenu {
             Submenu::CompleteTask => {
-                complete_task(storage, &mut terminal);
+                let deck = match scope {
+                    Scope::Deck(i) => Some(i),
+                    Scope::All => next_deck(decks, &mut session, last_deck),
+                };
+                if let Some(i) = deck {
+                    match complete_task(&mut decks[i].facade, &mut terminal) {
+                        ReviewOutcome::Reviewed(entry) => {
+                            decks[i].save_review(&entry)?;
+                            reviewed.push(i);
+                            last_deck = Some(i);
+                        }
+                        ReviewOutcome::Undo => undo(decks, &mut reviewed)?,
+                        ReviewOutcome::Aborted => (),
+                    }
+                }
             }
             Submenu::CreateTask => {
-                if let Some(blocks_with_answer) = get_blocks_with_answer(&mut terminal)? {
-                    storage.create_task(blocks_with_answer);
+                if let Some(i) = deck_in_scope(&mut terminal, decks, scope)?
+                    && let Some(blocks_with_answer) = get_blocks_with_answer(&mut terminal, "")?
+                {
+                    decks[i].facade.create_task(blocks_with_answer);
+                }
+            }
+            Submenu::BrowseTasks => {
+                if let Some(i) = deck_in_scope(&mut terminal, decks, scope)? {
+                    browser::show(&mut terminal, &mut decks[i])?;
                 }
             }
             Submenu::ModifyDesiredRetention => {
-                if let Some(desired_retention) = get_desired_retention(&mut terminal)? {
-                    storage.set_desired_retention(desired_retention);
+                if let Some(i) = deck_in_scope(&mut terminal, decks, scope)? {
+                    // Simulator models FSRS, its results mean nothing for other algorithms.
+                    let curve = if decks[i].facade.shared_state().algorithm() == Algorithm::Fsrs {
+                        Some(decks[i].retention_curve(|facade| {
+                            terminal.draw(|f| {
+                                f.render_widget(
+                                    ratatui::widgets::Paragraph::new(format!(
+                                        "Simulating '{}'",
+                                        facade.get_name()
+                                    )),
+                                    f.area(),
+                                );
+                            })?;
+                            Ok(())
+                        })?)
+                    } else {
+                        None
+                    };
+                    if let Some(desired_retention) = get_desired_retention(&mut terminal, curve)? {
+                        decks[i].facade.set_desired_retention(desired_retention);
+                    }
                 }
             }
             Submenu::Optimize => {
-                terminal.draw(|f| {
-                    f.render_widget(ratatui::widgets::Paragraph::new("Optimizing"), f.area());
-                })?;
-                if let Err(err) = storage.optimize() {
-                    save(PATH, storage)?;
-                    todo!("application can't handle error in optimization jet. Error: {err}");
+                for i in (0..decks.len()).filter(|&i| in_scope(i)) {
+                    let deck = &mut decks[i];
+                    // Other algorithms don't use weights, so optimizing them changes nothing.
+                    if deck.facade.shared_state().algorithm() != Algorithm::Fsrs {
+                        show_message(
+                            &mut terminal,
+                            format!(
+                                "Can't optimize '{}': optimization is available only for FSRS decks.",
+                                deck.facade.get_name()
+                            ),
+                        )?;
+                        continue;
+                    }
+                    terminal.draw(|f| {
+                        f.render_widget(
+                            ratatui::widgets::Paragraph::new(format!(
+                                "Optimizing '{}'",
+                                deck.facade.get_name()
+                            )),
+                            f.area(),
+                        );
+                    })?;
+                    let report = deck.facade.shared_state().weights().optimization_report(
+                        deck.facade.iter().filter_map(|(task, _)| task.fsrs()),
+                    );
+                    match report {
+                        Ok(report) => {
+                            if confirm_optimization(&mut terminal, deck.facade.get_name(), &report)?
+                            {
+                                let mut state = deck.facade.shared_state().clone();
+                                state.set_weights(report.weights);
+                                deck.facade.set_shared_state(state);
+                            }
+                        }
+                        Err(err) => show_message(
+                            &mut terminal,
+                            format!("Can't optimize '{}': {err}.", deck.facade.get_name()),
+                        )?,
+                    }
+                }
+            }
+            Submenu::Statistics => {
+                let mut decks = decks
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(i, _)| in_scope(*i))
+                    .map(|(_, deck)| deck)
+                    .collect::<Vec<_>>();
+                stats::show(&mut terminal, &mut decks)?;
+            }
+            Submenu::SwitchDeck => {
+                if let Some(picked) = pick_deck(&mut terminal, decks, true)? {
+                    scope = picked;
+                }
+            }
+            Submenu::FilterTags => {
+                if let Some(filter) = get_tag_filter(&mut terminal, decks)? {
+                    session.filter = filter;
+                    for deck in decks.iter_mut() {
+                        deck.facade.set_session_filter(session.filter.clone());
+                    }
+                }
+            }
+            Submenu::Save => {
+                for deck in decks.iter_mut() {
+                    deck.save()?;
                 }
             }
-            Submenu::Save => save(PATH, storage)?,
         }
     }
     drop(alt);
     Ok(())
 }
 
-fn get_desired_retention(terminal: &mut Terminal<impl Backend>) -> Result<Option<f64>> {
+/// Undoes the latest review of this session.
+fn undo(decks: &mut [Deck], reviewed: &mut Vec<usize>) -> Result<()> {
+    while let Some(i) = reviewed.pop() {
+        if let Some(id) = decks[i].facade.undo() {
+            return decks[i].save_undo(id);
+        }
+    }
+    Ok(())
+}
+
+/// Picks deck in combined queue. With round robin order decks with tasks to complete take turns
+/// after `last`, otherwise deck is picked with probability proportional to number of its tasks to
+/// complete, so every task to complete is equally likely to be shown.
+fn next_deck(decks: &[Deck], session: &mut Session, last: Option<usize>) -> Option<usize> {
+    if session.order == Order::TagRoundRobin {
+        let start = last.map_or(0, |i| i + 1);
+        return (0..decks.len())
+            .map(|i| (start + i) % decks.len())
+            .find(|&i| decks[i].facade.tasks_to_complete() > 0);
+    }
+    let total: usize = decks.iter().map(|x| x.facade.tasks_to_complete()).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut n = session.rng.random_range(0..total);
+    decks.iter().position(|deck| {
+        let to_complete = deck.facade.tasks_to_complete();
+        if n < to_complete {
+            true
+        } else {
+            n -= to_complete;
+            false
+        }
+    })
+}
+
+/// Returns current deck or, when all decks are selected, asks for one.
+fn deck_in_scope(
+    terminal: &mut Terminal<impl Backend>,
+    decks: &[Deck],
+    scope: Scope,
+) -> Result<Option<usize>> {
+    Ok(match scope {
+        Scope::Deck(i) => Some(i),
+        Scope::All => match pick_deck(terminal, decks, false)? {
+            Some(Scope::Deck(i)) => Some(i),
+            _ => None,
+        },
+    })
+}
+
+fn pick_deck(
+    terminal: &mut Terminal<impl Backend>,
+    decks: &[Deck],
+    allow_all: bool,
+) -> Result<Option<Scope>> {
+    let mut options = decks
+        .iter()
+        .map(|deck| {
+            format!(
+                "{} ({}/{})",
+                deck.facade.get_name(),
+                deck.facade.tasks_to_complete(),
+                deck.facade.tasks_total()
+            )
+        })
+        .collect::<Vec<_>>();
+    if allow_all {
+        options.push("all decks".into());
+    }
+    let request = vec![s_text_input_f::Block::OneOf(options)];
+    let (result_kind, answer) = ratatui_inputs::get_input(request, &mut |text| {
+        terminal
+            .draw(|f| f.render_widget(Paragraph::new(text), f.area()))
+            .map(|_| ())
+    })
+    .unwrap()?;
+    if result_kind == ResultKind::Canceled {
+        return Ok(None);
+    }
+    let answer: usize = answer[0][0].parse()?;
+    Ok(Some(if answer == decks.len() {
+        Scope::All
+    } else {
+        Scope::Deck(answer)
+    }))
+}
+
+/// Time per memorized task for each simulated retention.
+fn retention_chart<'a>(points: &'a [(f64, f64)]) -> Chart<'a> {
+    let (min_x, max_x) = points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &(x, _)| {
+            (min.min(x), max.max(x))
+        });
+    let max_y = points.iter().map(|&(_, y)| y).fold(0., f64::max);
+    Chart::new(vec![
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().cyan())
+            .data(points),
+    ])
+    .block(ratatui::widgets::Block::bordered().title("Seconds per memorized task in a year"))
+    .x_axis(
+        Axis::default()
+            .title("retention, %")
+            .bounds([min_x, max_x])
+            .labels([format!("{min_x:.0}"), format!("{max_x:.0}")]),
+    )
+    .y_axis(
+        Axis::default()
+            .bounds([0., max_y])
+            .labels(["0".to_owned(), format!("{max_y:.0}")]),
+    )
+}
+
+/// `curve` is shown next to input, it must be sorted by retention.
+/// It's `None` for decks, that don't use FSRS.
+fn get_desired_retention(
+    terminal: &mut Terminal<impl Backend>,
+    curve: Option<&[Simulation]>,
+) -> Result<Option<f64>> {
     fn parse_desired_retention(input: &str) -> Result<f64> {
         use std::str::FromStr;
         let number = f64::from_str(input)?;
@@ -180,6 +922,12 @@ fn get_desired_retention(terminal: &mut Terminal<impl Backend>) -> Result<Option
         Ok(number)
     }
 
+    let points = curve
+        .unwrap_or_default()
+        .iter()
+        .map(|x| (x.desired_retention * 100., x.cost_per_memorized()))
+        .filter(|(_, cost)| cost.is_finite())
+        .collect::<Vec<_>>();
     let (result_kind, user_input) = ratatui_inputs::get_text_input(&mut |styled, raw| {
         let layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -201,11 +949,45 @@ fn get_desired_retention(terminal: &mut Terminal<impl Backend>) -> Result<Option
                 );
                 f.render_widget(support_block, layout[1]);
 
-                let support_text = match parse_desired_retention(&raw) {
-                    Ok(number) => format!("{:.2}%", number * 100.),
+                let mut support_text = match parse_desired_retention(&raw) {
+                    Ok(number) => {
+                        let mut text = format!("{:.2}%", number * 100.);
+                        if let Some(closest) = curve.unwrap_or_default().iter().min_by(|a, b| {
+                            (a.desired_retention - number)
+                                .abs()
+                                .total_cmp(&(b.desired_retention - number).abs())
+                        }) && closest.cost_per_memorized().is_finite()
+                        {
+                            text += &format!(
+                                ", about {:.1}s per memorized task",
+                                closest.cost_per_memorized()
+                            );
+                        }
+                        text
+                    }
                     Err(err) => format!("Error: {err}."),
                 };
-                f.render_widget(ratatui::widgets::Paragraph::new(support_text), support_area);
+                match curve.map(optimal_retention) {
+                    Some(Some(optimal)) => {
+                        support_text += &format!(
+                            "\nRecommended {:.0}%: {:.1}s per memorized task.",
+                            optimal.desired_retention * 100.,
+                            optimal.cost_per_memorized()
+                        );
+                    }
+                    Some(None) => support_text += "\nNot enough tasks to recommend retention.",
+                    None => support_text += "\nRecommendation is available only for FSRS decks.",
+                }
+                let [text_area, chart_area] =
+                    Layout::vertical([Constraint::Length(4), Constraint::Fill(1)])
+                        .areas(support_area);
+                f.render_widget(
+                    ratatui::widgets::Paragraph::new(support_text).wrap(Wrap { trim: true }),
+                    text_area,
+                );
+                if !points.is_empty() {
+                    f.render_widget(retention_chart(&points), chart_area);
+                }
             })
             .map(|_| ())
     })?;
@@ -216,10 +998,83 @@ fn get_desired_retention(terminal: &mut Terminal<impl Backend>) -> Result<Option
     }
 }
 
-fn get_blocks_with_answer(
+/// Shows evaluation of current and optimized parameters.
+/// Returns whether user applies optimized ones.
+fn confirm_optimization(
     terminal: &mut Terminal<impl Backend>,
-) -> Result<Option<BlocksWithAnswer>> {
-    Ok(ratatui_inputs::get_blocks(&mut |styled, support_text| {
+    name: &str,
+    report: &OptimizationReport,
+) -> Result<bool> {
+    let evaluation = |label: &str, evaluation: &Evaluation| {
+        format!(
+            "{label}: log loss {:.4}, RMSE {:.2}%",
+            evaluation.log_loss,
+            evaluation.rmse * 100.
+        )
+    };
+    let verdict = if report.optimized.log_loss < report.current.log_loss {
+        "Optimized parameters predict your reviews better."
+    } else {
+        "Optimized parameters don't predict your reviews better, keeping current ones is advised."
+    };
+    let request = vec![
+        s_text_input_f::Block::Paragraph(vec![
+            format!("Optimization of '{name}' on recently reviewed tasks, lower is better:").into(),
+        ]),
+        s_text_input_f::Block::Paragraph(vec![evaluation("Current", &report.current).into()]),
+        s_text_input_f::Block::Paragraph(vec![
+            evaluation(
+                &format!("Optimized ({})", report.weights.version()),
+                &report.optimized,
+            )
+            .into(),
+        ]),
+        s_text_input_f::Block::Paragraph(vec![verdict.into()]),
+        s_text_input_f::Block::one_of(["Apply optimized parameters", "Keep current parameters"]),
+    ];
+    let (result_kind, answer) = ratatui_inputs::get_input(request, &mut |text| {
+        terminal
+            .draw(|f| f.render_widget(Paragraph::new(text), f.area()))
+            .map(|_| ())
+    })
+    .unwrap()?;
+    if result_kind == ResultKind::Canceled {
+        return Ok(false);
+    }
+    let answer = s_text_input_f::response_as_one_of(answer.last().unwrap().to_owned());
+    Ok(matches!(answer, Some(Ok(0))))
+}
+
+/// Shows text until user confirms it.
+fn show_message(terminal: &mut Terminal<impl Backend>, message: String) -> Result<()> {
+    let request = vec![
+        s_text_input_f::Block::Paragraph(vec![message.into()]),
+        s_text_input_f::Block::one_of(["OK"]),
+    ];
+    ratatui_inputs::get_input(request, &mut |text| {
+        terminal
+            .draw(|f| f.render_widget(Paragraph::new(text), f.area()))
+            .map(|_| ())
+    })
+    .unwrap()?;
+    Ok(())
+}
+
+/// Asks category of tags to restrict review session to, empty input removes restriction.
+fn get_tag_filter(
+    terminal: &mut Terminal<impl Backend>,
+    decks: &[Deck],
+) -> Result<Option<Option<String>>> {
+    fn parse_filter(input: &str) -> Result<Option<String>, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            Ok(None)
+        } else {
+            parse_tag(input).map(Some)
+        }
+    }
+
+    let (result_kind, user_input) = ratatui_inputs::get_text_input(&mut |styled, raw| {
         let layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Fill(1), Constraint::Fill(1)]);
@@ -230,7 +1085,6 @@ fn get_blocks_with_answer(
                 let input_block = ratatui::widgets::Block::bordered()
                     .border_type(ratatui::widgets::BorderType::Rounded);
                 let input_area = input_block.inner(layout[0]);
-
                 let support_block = ratatui::widgets::Block::new().padding(Padding::uniform(1));
                 let support_area = support_block.inner(layout[1]);
 
@@ -240,18 +1094,88 @@ fn get_blocks_with_answer(
                     input_area,
                 );
                 f.render_widget(support_block, layout[1]);
-                f.render_widget(ratatui::widgets::Paragraph::new(support_text), support_area);
+
+                let support_text = match parse_filter(&raw) {
+                    Ok(None) => "All tasks.".to_owned(),
+                    Ok(Some(category)) => {
+                        let tasks: usize = decks
+                            .iter()
+                            .map(|deck| deck.facade.tagged(&category).len())
+                            .sum();
+                        format!("{tasks} tasks with tags in '{category}'.")
+                    }
+                    Err(err) => format!("Error: {err}."),
+                };
+                f.render_widget(
+                    ratatui::widgets::Paragraph::new(support_text).wrap(Wrap { trim: true }),
+                    support_area,
+                );
             })
             .map(|_| ())
-    })?)
+    })?;
+    match result_kind {
+        ResultKind::Ok => Ok(parse_filter(&user_input).ok()),
+        ResultKind::Canceled => Ok(None),
+        _ => unreachable!(),
+    }
+}
+
+/// Multiline editor of task source, starting with `initial`.
+fn get_blocks_with_answer(
+    terminal: &mut Terminal<impl Backend>,
+    initial: &str,
+) -> Result<Option<BlocksWithAnswer>> {
+    Ok(ratatui_inputs::edit_blocks(
+        initial,
+        &mut |styled, support_text| {
+            let layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Fill(1), Constraint::Fill(1)]);
+            terminal
+                .draw(|f| {
+                    let layout = layout.split(f.area());
+
+                    let input_block = ratatui::widgets::Block::bordered()
+                        .border_type(ratatui::widgets::BorderType::Rounded);
+                    let input_area = input_block.inner(layout[0]);
+
+                    let support_block = ratatui::widgets::Block::new().padding(Padding::uniform(1));
+                    let support_area = support_block.inner(layout[1]);
+
+                    f.render_widget(input_block, layout[0]);
+                    f.render_widget(
+                        ratatui::widgets::Paragraph::new(styled).wrap(Wrap { trim: true }),
+                        input_area,
+                    );
+                    f.render_widget(support_block, layout[1]);
+                    f.render_widget(ratatui::widgets::Paragraph::new(support_text), support_area);
+                })
+                .map(|_| ())
+        },
+    )?)
 }
 
-fn complete_task(storage: &mut Facade, terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) {
-    let _ = storage.complete_task(&mut |id, blocks| {
-        let (_result_kind, answer) = ratatui_inputs::get_input(blocks, &mut |mut text| {
+enum ReviewOutcome {
+    Reviewed(ReviewLogEntry),
+    /// User pressed Ctrl+Z, current review is aborted and previous should be undone.
+    Undo,
+    Aborted,
+}
+
+fn complete_task(
+    storage: &mut Facade,
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+) -> ReviewOutcome {
+    let mut undo_requested = false;
+    let result = storage.complete_task(&mut |id, blocks| {
+        let (result_kind, answer) = ratatui_inputs::get_input(blocks, &mut |mut text| {
             use ratatui::style::Stylize;
             text.push_line("");
-            text.push_line(format!("ID {id}").dark_gray().italic());
+            text.push_line(
+                format!("ID {id}; Ctrl+Z to undo previous review")
+                    .dark_gray()
+                    .italic(),
+            );
 
             terminal
                 .draw(|f| f.render_widget(text, f.area()))
@@ -259,16 +1183,15 @@ fn complete_task(storage: &mut Facade, terminal: &mut Terminal<CrosstermBackend<
         })
         .transpose()?
         .unwrap_or((ResultKind::Ok, vec![vec![]]));
+        if result_kind == ResultKind::Undo {
+            undo_requested = true;
+            return Err(std::io::Error::other("undo requested"));
+        }
         Ok(answer)
     });
-}
-
-// FIXME: first create file, than rename it to `path` to not corrupt data
-fn save(path: &str, storage: &Facade) -> Result<()> {
-    writeln!(
-        std::fs::File::create(path)?,
-        "{}",
-        serde_json::to_string_pretty(storage)?
-    )?;
-    Ok(())
+    match result {
+        Ok(entry) => ReviewOutcome::Reviewed(entry),
+        Err(_) if undo_requested => ReviewOutcome::Undo,
+        Err(_) => ReviewOutcome::Aborted,
+    }
 }
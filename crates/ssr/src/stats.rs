@@ -0,0 +1,325 @@
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use crossterm::event::{Event, KeyEventKind};
+use ratatui::{
+    prelude::*,
+    widgets::{BarChart, Block, Paragraph, Wrap},
+};
+use ssr_algorithms::fsrs::level::Level;
+use ssr_core::{
+    task::Task,
+    tasks_facade::{TaskId, TasksFacade},
+};
+
+use crate::decks::Deck;
+
+const FORECAST_DAYS: usize = 30;
+const HISTORY_DAYS: usize = 30;
+/// Task is a leech, if it was forgotten at least this many times.
+const LEECH_LAPSES: usize = 4;
+const SHOWN_LEECHES: usize = 5;
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+const STABILITY_BUCKETS: [(&str, f32); 6] = [
+    ("<1d", 1.),
+    ("<1w", 7.),
+    ("<1m", 30.),
+    ("<3m", 90.),
+    ("<1y", 365.),
+    (">1y", f32::INFINITY),
+];
+
+struct Leech {
+    id: TaskId,
+    lapses: usize,
+    failed: bool,
+    label: String,
+}
+
+/// Everything shown on statistics screen.
+struct Statistics {
+    tasks_total: usize,
+    not_started: usize,
+    /// Tasks due on each of next days, overdue tasks are due today.
+    forecast: [u64; FORECAST_DAYS],
+    /// Reviews done each of previous days, today is first.
+    reviews: [u64; HISTORY_DAYS],
+    /// Share of successful reviews of already started tasks for last [`HISTORY_DAYS`].
+    achieved_retention: Option<f64>,
+    /// Desired retention, weighted by number of tasks in each deck.
+    desired_retention: f64,
+    stability: [u64; STABILITY_BUCKETS.len()],
+    /// Difficulty is in `1..=10`.
+    difficulty: [u64; 10],
+    leeches: Vec<Leech>,
+}
+
+fn days(duration: Duration) -> usize {
+    usize::try_from(duration.as_secs() / DAY.as_secs()).unwrap_or(usize::MAX)
+}
+
+/// Text of paragraphs, where placeholders are replaced with `___`.
+pub fn task_label(blocks: &s_text_input_f::Blocks) -> String {
+    let label = blocks
+        .iter()
+        .filter_map(|block| match block {
+            s_text_input_f::Block::Paragraph(items) => Some(
+                items
+                    .iter()
+                    .map(|item| match item {
+                        s_text_input_f::ParagraphItem::Text(text) => text.as_str(),
+                        s_text_input_f::ParagraphItem::Placeholder => "___",
+                    })
+                    .collect::<String>(),
+            ),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    label.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl Statistics {
+    fn collect(decks: &mut [&mut Deck]) -> Result<Self> {
+        let now = SystemTime::now();
+        let mut stats = Self {
+            tasks_total: 0,
+            not_started: 0,
+            forecast: [0; FORECAST_DAYS],
+            reviews: [0; HISTORY_DAYS],
+            achieved_retention: None,
+            desired_retention: 0.,
+            stability: [0; STABILITY_BUCKETS.len()],
+            difficulty: [0; 10],
+            leeches: Vec::new(),
+        };
+        let (mut recalled, mut recalls) = (0_u32, 0_u32);
+        let mut desired_retention = 0.;
+        for deck in decks.iter_mut() {
+            for entry in deck.reviews()? {
+                let Ok(ago) = now.duration_since(entry.review.timestamp) else {
+                    continue;
+                };
+                let ago = days(ago);
+                if ago >= HISTORY_DAYS {
+                    continue;
+                }
+                stats.reviews[ago] += 1;
+                // Only FSRS logged reviews before `recalled` was recorded.
+                let was_recalled = entry.review.recalled.or_else(|| {
+                    serde_json::from_value::<Level>(entry.review.state_before)
+                        .ok()
+                        .filter(|level| !matches!(level, Level::NotStarted))
+                        .map(|_| entry.review.grade.is_correct())
+                });
+                if let Some(was_recalled) = was_recalled {
+                    recalls += 1;
+                    if was_recalled {
+                        recalled += 1;
+                    }
+                }
+            }
+
+            let facade = &deck.facade;
+            let state = facade.shared_state();
+            let fsrs = state.weights().fsrs();
+            #[allow(clippy::cast_precision_loss)]
+            {
+                desired_retention += facade.get_desired_retention() * facade.tasks_total() as f64;
+            }
+            for (task, id) in facade.iter() {
+                stats.tasks_total += 1;
+                if task.is_new() {
+                    stats.not_started += 1;
+                    continue;
+                }
+                let due = task.next_repetition(state, facade.get_desired_retention());
+                let due_in = days(due.duration_since(now).unwrap_or_default());
+                if let Some(day) = stats.forecast.get_mut(due_in) {
+                    *day += 1;
+                }
+                // Memory state and lapses are known only for FSRS.
+                let Some(level) = task.fsrs().map(|task| task.level()) else {
+                    continue;
+                };
+                let Some(memory_state) = level.memory_state(&fsrs) else {
+                    continue;
+                };
+                let bucket = STABILITY_BUCKETS
+                    .iter()
+                    .position(|(_, limit)| memory_state.stability < *limit)
+                    .unwrap_or(STABILITY_BUCKETS.len() - 1);
+                stats.stability[bucket] += 1;
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let difficulty = (memory_state.difficulty.round().clamp(1., 10.) as usize) - 1;
+                stats.difficulty[difficulty] += 1;
+
+                let lapses = level.lapses();
+                if lapses >= LEECH_LAPSES {
+                    stats.leeches.push(Leech {
+                        id,
+                        lapses,
+                        failed: level.failed(),
+                        label: task_label(&task.get_blocks().blocks),
+                    });
+                }
+            }
+        }
+        if recalls > 0 {
+            stats.achieved_retention = Some(f64::from(recalled) / f64::from(recalls));
+        }
+        if stats.tasks_total > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            {
+                stats.desired_retention = desired_retention / stats.tasks_total as f64;
+            }
+        }
+        stats
+            .leeches
+            .sort_by(|a, b| b.lapses.cmp(&a.lapses).then(b.failed.cmp(&a.failed)));
+        Ok(stats)
+    }
+}
+
+fn bar_chart<'a>(title: &'a str, data: &'a [(String, u64)], area: Rect) -> BarChart<'a> {
+    let data = data
+        .iter()
+        .map(|(label, value)| (label.as_str(), *value))
+        .collect::<Vec<_>>();
+    let bars = u16::try_from(data.len().max(1)).unwrap_or(u16::MAX);
+    let bar_width = (area.width.saturating_sub(2) / bars)
+        .saturating_sub(1)
+        .max(1);
+    BarChart::default()
+        .block(Block::bordered().title(title))
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(Style::new().cyan())
+        .value_style(Style::new().black().on_cyan())
+        .data(&data)
+}
+
+fn render(stats: &Statistics, frame: &mut Frame) {
+    let [summary, forecast, reviews, memory, leeches, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Length(u16::try_from(SHOWN_LEECHES).unwrap_or_default() + 2),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let achieved = stats.achieved_retention.map_or_else(
+        || "no reviews yet".to_owned(),
+        |x| format!("{:.1}%", x * 100.),
+    );
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(format!(
+                "Tasks: {}, not started: {}",
+                stats.tasks_total, stats.not_started
+            )),
+            Line::from(format!(
+                "Retention for last {HISTORY_DAYS} days: {achieved} (desired {:.1}%)",
+                stats.desired_retention * 100.
+            )),
+        ])
+        .block(Block::new().title("Statistics".bold())),
+        summary,
+    );
+
+    let forecast_data = stats
+        .forecast
+        .iter()
+        .enumerate()
+        .map(|(day, count)| (day.to_string(), *count))
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        bar_chart("Due, in days", &forecast_data, forecast),
+        forecast,
+    );
+
+    let reviews_data = stats
+        .reviews
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(ago, count)| (ago.to_string(), *count))
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        bar_chart("Reviews, days ago", &reviews_data, reviews),
+        reviews,
+    );
+
+    let [stability, difficulty] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(memory);
+    let stability_data = STABILITY_BUCKETS
+        .iter()
+        .zip(stats.stability)
+        .map(|((label, _), count)| ((*label).to_owned(), count))
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        bar_chart("Stability", &stability_data, stability),
+        stability,
+    );
+    let difficulty_data = stats
+        .difficulty
+        .iter()
+        .enumerate()
+        .map(|(i, count)| ((i + 1).to_string(), *count))
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        bar_chart("Difficulty", &difficulty_data, difficulty),
+        difficulty,
+    );
+
+    let leeches_text = if stats.leeches.is_empty() {
+        vec![Line::from("No leeches").dark_gray()]
+    } else {
+        stats
+            .leeches
+            .iter()
+            .take(SHOWN_LEECHES)
+            .map(|leech| {
+                let lapses = Span::from(format!("{:>3} lapses ", leech.lapses));
+                let lapses = if leech.failed {
+                    lapses.red()
+                } else {
+                    lapses.yellow()
+                };
+                Line::from(vec![
+                    lapses,
+                    Span::from(leech.label.clone()),
+                    Span::from(format!(" (ID {})", leech.id)).dark_gray(),
+                ])
+            })
+            .collect()
+    };
+    frame.render_widget(
+        Paragraph::new(leeches_text)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(format!(
+                "Leeches ({}), forgotten at least {LEECH_LAPSES} times",
+                stats.leeches.len()
+            ))),
+        leeches,
+    );
+    frame.render_widget(
+        Line::from("Press any key to return").dark_gray().italic(),
+        footer,
+    );
+}
+
+/// Shows statistics of `decks` until any key is pressed.
+pub fn show(terminal: &mut Terminal<impl Backend>, decks: &mut [&mut Deck]) -> Result<()> {
+    let stats = Statistics::collect(decks)?;
+    loop {
+        terminal.draw(|frame| render(&stats, frame))?;
+        match crossterm::event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => return Ok(()),
+            _ => (),
+        }
+    }
+}
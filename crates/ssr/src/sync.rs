@@ -0,0 +1,393 @@
+//! Sync of deck with directory of markdown files, that are the source of truth for its tasks.
+//!
+//! Each file contains tasks in `ssr add` syntax, separated by `---` lines. Tags are set by task
+//! front matter. Synced tasks get `<!-- ssr-id: <id> -->` comment, so edited task keeps its
+//! progress.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use s_text_input_f::BlocksWithAnswer;
+use ssr_core::{
+    storage,
+    task::Task,
+    tasks_facade::{TaskId, TasksFacade},
+};
+
+use crate::{Facade, export::TASK_SEPARATOR};
+
+const ID_PREFIX: &str = "ssr-id:";
+
+/// Result of [`sync`], whose id comments aren't written yet.
+#[must_use]
+pub struct Synced {
+    pub summary: Summary,
+    files: Vec<File>,
+}
+
+impl Synced {
+    /// Writes id comments of created tasks. Must be called after deck is saved, otherwise files
+    /// could point to tasks, that deck doesn't have.
+    pub fn write_ids(&self) -> Result<()> {
+        for file in self.files.iter().filter(|file| file.changed) {
+            file.write()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Summary {
+    pub created: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+struct File {
+    path: PathBuf,
+    /// Lines of each task, separators are not included.
+    chunks: Vec<Vec<String>>,
+    trailing_newline: bool,
+    changed: bool,
+}
+
+impl File {
+    fn read(path: PathBuf) -> Result<Self> {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("can't read '{}'", path.display()))?;
+        let mut chunks = vec![Vec::new()];
+        for line in content.lines() {
+            if line.trim() == TASK_SEPARATOR {
+                chunks.push(Vec::new());
+            } else {
+                chunks.last_mut().unwrap().push(line.to_owned());
+            }
+        }
+        Ok(Self {
+            path,
+            chunks,
+            trailing_newline: content.ends_with('\n'),
+            changed: false,
+        })
+    }
+
+    /// Replaces id comment of task, or inserts it before the task.
+    fn set_id(&mut self, chunk: usize, id: TaskId) {
+        let lines = &mut self.chunks[chunk];
+        lines.retain(|line| parse_id(line).is_none());
+        let start = lines
+            .iter()
+            .position(|line| !line.trim().is_empty())
+            .unwrap_or(lines.len());
+        lines.insert(start, format!("<!-- {ID_PREFIX} {id} -->"));
+        self.changed = true;
+    }
+
+    fn write(&self) -> Result<()> {
+        let mut content = self
+            .chunks
+            .iter()
+            .map(|lines| lines.join("\n"))
+            .collect::<Vec<_>>()
+            .join(&format!("\n{TASK_SEPARATOR}\n"));
+        if self.trailing_newline {
+            content.push('\n');
+        }
+        storage::write_atomically(&self.path, |file| file.write_all(content.as_bytes()))
+            .with_context(|| format!("can't write '{}'", self.path.display()))
+    }
+}
+
+fn parse_id(line: &str) -> Option<TaskId> {
+    line.trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim()
+        .strip_prefix(ID_PREFIX)?
+        .trim()
+        .parse()
+        .ok()
+}
+
+struct Card {
+    file: usize,
+    chunk: usize,
+    id: Option<TaskId>,
+    blocks: BlocksWithAnswer,
+    tags: Vec<String>,
+}
+
+/// Markdown files in `dir` and its subdirectories, sorted.
+fn markdown_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("can't read '{}'", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(markdown_files(&path)?);
+        } else if path.extension().is_some_and(|x| x == "md") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Makes tasks of `facade` match tasks in `dir`: new tasks are created, edited are updated
+/// keeping their progress, missing are removed. Id comments of new tasks are written to files by
+/// [`Synced::write_ids`].
+///
+/// Nothing is changed if any task can't be parsed.
+pub fn sync(dir: &Path, facade: &mut Facade) -> Result<Synced> {
+    let mut files = markdown_files(dir)?
+        .into_iter()
+        .map(File::read)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut cards = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    for (file_index, file) in files.iter().enumerate() {
+        for (chunk, lines) in file.chunks.iter().enumerate() {
+            let mut id = None;
+            let mut source = Vec::new();
+            for line in lines {
+                match parse_id(line) {
+                    Some(found) => id = Some(found),
+                    None => source.push(line.as_str()),
+                }
+            }
+            let source = source.join("\n");
+            let source = source.trim();
+            if source.is_empty() {
+                continue;
+            }
+            let location = format!("{}, task {}", file.path.display(), chunk + 1);
+            match s_text_input_f_parser::parse_task(source) {
+                Ok(task) if task.blocks.answer.iter().all(Vec::is_empty) => {
+                    errors.push(format!(
+                        "{location}: task must contain interactive elements"
+                    ));
+                }
+                Ok(task) => cards.push(Card {
+                    file: file_index,
+                    chunk,
+                    // Copied task becomes new one.
+                    id: id.filter(|id| seen.insert(*id)),
+                    blocks: task.blocks,
+                    tags: task.front_matter.tags,
+                }),
+                Err(errs) => {
+                    errors.extend(errs.into_iter().map(|err| format!("{location}: {err}")))
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        bail!(
+            "nothing is synced, fix parsing errors:\n{}",
+            errors.join("\n")
+        );
+    }
+
+    let mut existing = facade
+        .iter()
+        .map(|(task, id)| {
+            let tags = facade.tags(id).unwrap_or_default().to_vec();
+            (id, (task.get_blocks(), tags))
+        })
+        .collect::<HashMap<_, _>>();
+    if !existing.is_empty()
+        && !cards
+            .iter()
+            .any(|card| card.id.is_some_and(|id| existing.contains_key(&id)))
+    {
+        bail!(
+            "deck '{}' already has {} tasks, that aren't in '{}'; sync would remove all of them, use empty deck",
+            facade.get_name(),
+            existing.len(),
+            dir.display()
+        );
+    }
+
+    let mut summary = Summary::default();
+    for card in cards {
+        match card.id.and_then(|id| Some((id, existing.remove(&id)?))) {
+            Some((_, (blocks, tags))) if blocks == card.blocks && tags == card.tags => {
+                summary.unchanged += 1;
+            }
+            Some((id, (blocks, _))) => {
+                if blocks != card.blocks {
+                    facade.update_blocks(id, card.blocks);
+                }
+                facade.set_tags(id, card.tags);
+                summary.updated += 1;
+            }
+            None => {
+                let id = facade.create_task(card.blocks);
+                facade.set_tags(id, card.tags);
+                files[card.file].set_id(card.chunk, id);
+                summary.created += 1;
+            }
+        }
+    }
+    for id in existing.into_keys() {
+        facade.remove(id);
+        summary.removed += 1;
+    }
+    Ok(Synced { summary, files })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use ssr_algorithms::any;
+    use ssr_core::task::{Grade, PastReview};
+
+    use super::*;
+    use crate::DEFAULT_DESIRED_RETENTION;
+
+    fn facade() -> Facade<'static> {
+        Facade::new("test".into(), DEFAULT_DESIRED_RETENTION)
+    }
+
+    fn blocks(source: &str) -> BlocksWithAnswer {
+        s_text_input_f_parser::parse_blocks(source).unwrap()
+    }
+
+    fn sync_ids(dir: &Path, facade: &mut Facade) -> Summary {
+        let synced = sync(dir, facade).unwrap();
+        synced.write_ids().unwrap();
+        synced.summary
+    }
+
+    fn ids(path: &Path) -> Vec<TaskId> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .filter_map(parse_id)
+            .collect()
+    }
+
+    fn task_ids(facade: &Facade) -> HashSet<TaskId> {
+        facade.iter().map(|(_, id)| id).collect()
+    }
+
+    #[test]
+    fn new_tasks_get_ids_after_deck_is_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.md");
+        let source = "first `a`\n---\nsecond `b`\n";
+        fs::write(&path, source).unwrap();
+        let mut facade = facade();
+
+        let synced = sync(dir.path(), &mut facade).unwrap();
+        assert_eq!(synced.summary.created, 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), source);
+        synced.write_ids().unwrap();
+
+        let ids = ids(&path);
+        assert_eq!(
+            ids.iter().copied().collect::<HashSet<_>>(),
+            task_ids(&facade)
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!(
+                "<!-- ssr-id: {} -->\nfirst `a`\n---\n<!-- ssr-id: {} -->\nsecond `b`\n",
+                ids[0], ids[1]
+            )
+        );
+
+        let summary = sync_ids(dir.path(), &mut facade);
+        assert_eq!((summary.created, summary.unchanged), (0, 2));
+    }
+
+    #[test]
+    fn copied_id_becomes_new_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.md");
+        fs::write(&path, "task `a`").unwrap();
+        let mut facade = facade();
+        sync_ids(dir.path(), &mut facade);
+
+        let task = fs::read_to_string(&path).unwrap();
+        fs::write(&path, format!("{task}\n---\n{task}")).unwrap();
+        let summary = sync_ids(dir.path(), &mut facade);
+        assert_eq!((summary.created, summary.unchanged), (1, 1));
+
+        let ids = ids(&path);
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(ids.into_iter().collect::<HashSet<_>>(), task_ids(&facade));
+    }
+
+    #[test]
+    fn edited_task_keeps_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut facade = facade();
+        let mut task = any::Task::new(blocks("old `a`"));
+        let review = PastReview {
+            timestamp: SystemTime::now(),
+            grade: Grade::Good,
+        };
+        task.replay(&mut any::State::default(), &[review]);
+        let id = facade.insert(task);
+        fs::write(
+            dir.path().join("tasks.md"),
+            format!("<!-- ssr-id: {id} -->\nnew `b`"),
+        )
+        .unwrap();
+
+        let summary = sync_ids(dir.path(), &mut facade);
+        assert_eq!(summary.updated, 1);
+        let (task, _) = facade.iter().find(|(_, x)| *x == id).unwrap();
+        assert_eq!(task.get_blocks(), blocks("new `b`"));
+        assert!(!task.is_new());
+        assert_eq!(task.history(), [review]);
+    }
+
+    #[test]
+    fn missing_task_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.md");
+        fs::write(&path, "first `a`\n---\nsecond `b`").unwrap();
+        let mut facade = facade();
+        sync_ids(dir.path(), &mut facade);
+
+        let content = fs::read_to_string(&path).unwrap();
+        let (first, _) = content
+            .split_once(&format!("\n{TASK_SEPARATOR}\n"))
+            .unwrap();
+        fs::write(&path, first).unwrap();
+        let summary = sync_ids(dir.path(), &mut facade);
+        assert_eq!((summary.removed, summary.unchanged), (1, 1));
+        assert_eq!(
+            task_ids(&facade),
+            ids(&path).into_iter().collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn refuses_to_remove_all_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.md");
+        fs::write(&path, "unrelated `a`").unwrap();
+        let mut facade = facade();
+        let id = facade.create_task(blocks("existing `b`"));
+
+        let err = sync(dir.path(), &mut facade).err().unwrap();
+        assert!(
+            err.to_string().contains("would remove all of them"),
+            "{err}"
+        );
+        assert_eq!(task_ids(&facade), HashSet::from([id]));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "unrelated `a`");
+    }
+}